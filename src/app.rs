@@ -1,57 +1,195 @@
 use eframe::{
     Frame,
     egui::{
-        self, Color32, FontData, FontFamily, FontId, Id, Layout, Sense, TextFormat, Ui,
-        text::LayoutJob,
+        self, Align, Color32, FontData, FontFamily, FontId, Id, Layout, RichText, Sense,
+        TextFormat, Ui, text::LayoutJob,
     },
     epaint::text::{FontInsert, FontPriority, InsertFontFamily},
 };
 
-use crate::{tool_card::ToolCard, tools::*};
+use crate::{
+    clipboard::PasteDetector,
+    deep_link::DeepLink,
+    palette::{self, CommandPalette},
+    persistence::SavedState,
+    settings,
+    tool_card::ToolCard,
+    tools::*,
+};
+
+/// 已创建的工具及其注册信息
+pub(crate) struct ToolSlot {
+    pub(crate) info: ToolInfo,
+    pub(crate) tool: Box<dyn ToolItem>,
+    pub(crate) hidden: bool,
+    /// 在注册列表中的位置
+    pub(crate) default_order: usize,
+}
 
-/// We derive Deserialize/Serialize so we can persist app state on shutdown.
-#[cfg_attr(feature = "persistence", derive(serde::Deserialize, serde::Serialize))]
-#[cfg_attr(feature = "persistence", serde(default))] // if we add new fields, give them default values when deserializing old state
 pub struct App {
-    #[cfg_attr(feature = "persistence", serde(skip))]
-    tools: Vec<Box<dyn ToolItem>>,
+    /// 按边栏中的顺序排列
+    tools: Vec<ToolSlot>,
     active_tool: Option<usize>,
+    show_settings: bool,
+    show_experimental: Option<bool>,
+    palette: CommandPalette,
+    paste_detector: PasteDetector,
+    /// 分享链接的网页地址，桌面端为空
+    share_base: String,
 }
 
 impl App {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        Self::with_registry(cc, ToolRegistry::builtin())
+    }
+
+    /// 使用自定义的工具列表创建应用，可在 [`ToolRegistry::builtin`] 的基础上注册内部工具
+    pub fn with_registry(cc: &eframe::CreationContext<'_>, registry: ToolRegistry) -> Self {
         add_font(&cc.egui_ctx);
-        Self {
-            tools: vec![
-                Box::new(TimestampConverter::default()),
-                Box::new(JsonConverter::default()),
-                Box::new(UrlConverter::default()),
-                Box::new(LineFormatter::default()),
-                Box::new(IssueJump::default()),
-                Box::new(TaskGraphJump::default()),
-                #[cfg(debug_assertions)]
-                Box::new(LogRetriever::default()),
-            ],
-            active_tool: Some(0),
+        let mut app = Self {
+            tools: registry
+                .iter()
+                .filter(|info| info.availability.on_this_platform())
+                .enumerate()
+                .map(|(default_order, info)| ToolSlot {
+                    info: info.clone(),
+                    tool: (info.create)(),
+                    hidden: false,
+                    default_order,
+                })
+                .collect(),
+            active_tool: None,
+            show_settings: false,
+            show_experimental: None,
+            palette: CommandPalette::default(),
+            paste_detector: PasteDetector::default(),
+            share_base: String::new(),
+        };
+        if let Some(state) = cc.storage.and_then(SavedState::load) {
+            app.restore(state);
+        }
+        if app.active_tool.is_none() {
+            app.active_tool = app.first_visible();
         }
+        #[cfg(target_arch = "wasm32")]
+        {
+            // eframe 提供的 `Location::url` 和 `hash` 已经解码过，直接读取原始地址，只解码一次
+            let href = web_sys::window()
+                .and_then(|window| window.location().href().ok())
+                .unwrap_or_default();
+            app.share_base = DeepLink::base_url(&href).to_owned();
+            if let Some(link) = DeepLink::parse(&href) {
+                app.open_link(&link, &cc.egui_ctx);
+            }
+        }
+        app
+    }
+
+    /// 切换到链接指定的工具并填入输入，工具不存在或被隐藏时忽略
+    pub fn open_link(&mut self, link: &DeepLink, ctx: &egui::Context) {
+        let Some(idx) = (0..self.tools.len())
+            .find(|&idx| self.tools[idx].info.id == link.tool && self.is_visible(idx))
+        else {
+            log::warn!("链接中的工具不存在：{}", link.tool);
+            return;
+        };
+        let tool = &mut self.tools[idx].tool;
+        if let Some(input) = &link.input {
+            tool.set_input(input);
+        }
+        if let Some(action) = &link.action {
+            tool.run_action(action, ctx);
+        }
+        self.active_tool = Some(idx);
+        self.show_settings = false;
+    }
+
+    fn restore(&mut self, mut state: SavedState) {
+        for slot in &mut self.tools {
+            if let Some(tool_state) = state.tools.remove(slot.info.id) {
+                slot.tool.load_state(tool_state);
+            }
+            slot.hidden = state.hidden_tools.contains(slot.info.id);
+        }
+        self.tools.sort_by_key(|slot| {
+            state
+                .tool_order
+                .iter()
+                .position(|id| id == slot.info.id)
+                .unwrap_or(usize::MAX)
+        });
+        self.show_experimental = state.show_experimental;
+        self.active_tool = state
+            .active_tool
+            .and_then(|id| self.tools.iter().position(|slot| slot.info.id == id))
+            .filter(|&idx| self.is_visible(idx));
+    }
+
+    fn snapshot(&self) -> SavedState {
+        SavedState {
+            active_tool: self
+                .active_tool
+                .map(|idx| self.tools[idx].info.id.to_owned()),
+            tools: self
+                .tools
+                .iter()
+                .map(|slot| (slot.info.id.to_owned(), slot.tool.save_state()))
+                .filter(|(_, state)| !state.is_null())
+                .collect(),
+            tool_order: self
+                .tools
+                .iter()
+                .map(|slot| slot.info.id.to_owned())
+                .collect(),
+            hidden_tools: self
+                .tools
+                .iter()
+                .filter(|slot| slot.hidden)
+                .map(|slot| slot.info.id.to_owned())
+                .collect(),
+            show_experimental: self.show_experimental,
+            ..Default::default()
+        }
+    }
+
+    fn is_visible(&self, idx: usize) -> bool {
+        let slot = &self.tools[idx];
+        !slot.hidden
+            && (slot.info.availability != Availability::Experimental
+                || self.show_experimental.unwrap_or(cfg!(debug_assertions)))
+    }
+
+    fn first_visible(&self) -> Option<usize> {
+        (0..self.tools.len()).find(|&idx| self.is_visible(idx))
+    }
+
+    fn visible_tools(&self) -> Vec<usize> {
+        (0..self.tools.len())
+            .filter(|&idx| self.is_visible(idx))
+            .collect()
     }
 }
 
 impl eframe::App for App {
     /// Called by the frame work to save state before shutdown.
     /// Note that you must enable the `persistence` feature for this to work.
-    #[cfg(feature = "persistence")]
-    fn save(&mut self, storage: &mut dyn epi::Storage) {
-        epi::set_value(storage, epi::APP_KEY, self);
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.snapshot().save(storage);
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
     /// Put your widgets into a `SidePanel`, `TopPanel`, `CentralPanel`, `Window` or `Area`.
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
-        let Self {
-            tools,
-            active_tool: active_tool_idx,
-        } = self;
+        // 命令面板，需要在其他控件之前处理按键
+        let visible = self.visible_tools();
+        if let Some((idx, action)) = self.palette.show(ctx, &self.tools, &visible) {
+            self.active_tool = Some(idx);
+            self.show_settings = false;
+            if let Some(action) = action {
+                self.tools[idx].tool.run_action(action, ctx);
+            }
+        }
+
         // 标题栏
         if cfg!(target_arch = "wasm32") {
             egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
@@ -131,36 +269,92 @@ impl eframe::App for App {
         */
         // 边栏
         egui::SidePanel::left("side_panel").show(ctx, |ui| {
-            for (i, tool) in tools.iter().enumerate() {
+            let mut last_category = None;
+            for (n, &i) in visible.iter().enumerate() {
+                let slot = &self.tools[i];
+                // 分组标题
+                if last_category != Some(slot.info.category) {
+                    if last_category.is_some() {
+                        ui.add_space(8.0);
+                    }
+                    ui.label(RichText::new(slot.info.category.label()).small().weak());
+                    last_category = Some(slot.info.category);
+                } else if n != 0 {
+                    ui.separator();
+                }
                 // 选项卡
-                let mut card = ToolCard::new(tool.name(), tool.description());
+                let mut card =
+                    ToolCard::new(slot.info.icon, slot.tool.name(), slot.tool.description());
                 // 使用局部样式更新
                 let response = ui
                     .scope(|ui| {
-                        card.update(
-                            ui,
-                            active_tool_idx.is_some() && active_tool_idx.unwrap() == i,
-                        );
+                        card.update(ui, !self.show_settings && self.active_tool == Some(i));
                     })
                     .response;
                 // 检查点击
-                let response =
-                    ui.interact(response.rect, Id::new("tool_card").with(i), Sense::click());
+                let response = ui.interact(
+                    response.rect,
+                    Id::new("tool_card").with(slot.info.id),
+                    Sense::click(),
+                );
                 if response.clicked() {
-                    *active_tool_idx = Some(i);
-                }
-                if i != tools.len() - 1 {
-                    ui.separator();
+                    self.active_tool = Some(i);
+                    self.show_settings = false;
                 }
             }
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.toggle_value(&mut self.show_settings, "⚙ 设置");
+                let shortcut = ui.ctx().format_shortcut(&palette::SHORTCUT);
+                ui.weak(format!("{shortcut} 命令面板"));
+            });
             egui::warn_if_debug_build(ui);
         });
 
+        // 粘贴内容识别
+        let active_tool = self.active_tool.filter(|_| !self.show_settings);
+        if let Some(suggestion) = self
+            .paste_detector
+            .show(ctx, &self.tools, &visible, active_tool)
+        {
+            let tool = &mut self.tools[suggestion.tool].tool;
+            tool.set_input(&suggestion.input);
+            if let Some(action) = suggestion.action {
+                tool.run_action(action, ctx);
+            }
+            self.active_tool = Some(suggestion.tool);
+            self.show_settings = false;
+        }
+
         // 主面板
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.spacing_mut().item_spacing = (16.0, 8.0).into();
-            if let Some(idx) = active_tool_idx {
-                tools[*idx].update(ui);
+            if self.show_settings {
+                let active_id = self.active_tool.map(|idx| self.tools[idx].info.id);
+                let mut show_experimental =
+                    self.show_experimental.unwrap_or(cfg!(debug_assertions));
+                if settings::show(ui, &mut self.tools, &mut show_experimental) {
+                    self.show_experimental = Some(show_experimental);
+                }
+                // 工具被移动或隐藏后，重新定位当前工具
+                self.active_tool = active_id
+                    .and_then(|id| self.tools.iter().position(|slot| slot.info.id == id))
+                    .filter(|&idx| self.is_visible(idx))
+                    .or_else(|| self.first_visible());
+            } else if let Some(idx) = self.active_tool {
+                let slot = &mut self.tools[idx];
+                if let Some(input) = slot.tool.share_input() {
+                    ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
+                        let share = ui
+                            .small_button("🔗 复制分享链接")
+                            .on_hover_text("网页端可直接打开；桌面端可作为参数传给 handy");
+                        if share.clicked() {
+                            let link = DeepLink::new(slot.info.id, Some(&input));
+                            ui.ctx().copy_text(link.to_url(&self.share_base));
+                        }
+                    });
+                }
+                slot.tool.update(ui);
             }
         });
     }
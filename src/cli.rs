@@ -0,0 +1,472 @@
+//! 命令行入口，复用 `handy::engine` 中的转换逻辑。
+//!
+//! 退出码：0 成功；1 有输出但存在警告；2 输入无效、没有输出；64 参数错误。
+
+use std::{
+    fs,
+    io::{self, Read, Write},
+    process::ExitCode,
+};
+
+use handy::engine::{
+    json::{self, BinaryEncoding, Conversion, Formatter, InputMode},
+    stack::{self, LineFormat},
+    timestamp::{self, Epoch, TimeUnit, Zone},
+    url,
+};
+use serde_json::Value;
+
+const EXIT_WARNING: u8 = 1;
+const EXIT_INVALID: u8 = 2;
+const EXIT_USAGE: u8 = 64;
+
+const USAGE: &str = "\
+用法：handy [子命令] [选项] [输入]
+
+不带子命令时启动图形界面。
+
+子命令：
+  ts [--unit s|ms|us|ns] [--tz 时区] [--epoch 基准] [值...]
+        时间戳与时间字符串互转，默认使用本地时区，时区可以是 UTC、+08:00 或 Asia/Shanghai；
+        基准可以是 unix（默认）、gps、filetime、cocoa、excel 或 ros；
+        值为 now 时输出当前时间；未给出值时逐行读取标准输入
+  json [--pretty|--minimize|--raw|--canonical] [--serialize|--deserialize] [--python|--json5|--ndjson]
+       [--to-python] [--query 查询] [文件]
+        JSON 格式处理，默认展开；--python 按 Python 字面量解析输入，--json5 允许注释和末尾逗号等，
+        --ndjson 逐行解析并合并为数组；--to-python 输出 Python 字面量；--canonical 输出 RFC 8785 规范化 JSON；
+        查询可以是 JSONPath 或 jq 子集，每个结果输出一行；未给出文件时读取标准输入
+  json [--indent 宽度] [--sort-keys] [--sort-arrays] [--drop-nulls] [--drop-empty] [--ascii] [文件]
+        输出选项：展开时的缩进空格数（默认 2）、递归按键排序、元素都是标量的数组排序、
+        删除值为 null 或空对象、空数组的键、非 ASCII 字符转义为 \\uXXXX
+  json --from 格式 --to 格式 [--base64] [--pretty|--minimize] [文件]
+        JSON 与其他格式互转，格式可以是 json、yaml、toml、csv、msgpack 或 cbor；
+        MessagePack 和 CBOR 默认以十六进制文本表示，--base64 改用 base64；有损转换时退出码为 1
+  json --schema Schema文件 [文件] | json --infer-schema [文件]
+        按 JSON Schema（draft 2020-12）校验，每处错误输出一行，不符合时退出码为 2；
+        --infer-schema 从示例文档推断 Schema
+  json --types rust|typescript|python [文件]
+        从示例生成带 serde 注解的 Rust 结构体、TypeScript 接口或 Python dataclass；
+        输入为数组（或 --ndjson）时每个元素作为一个示例，合并推断可选字段
+  json --diff 右侧文件 [--ignore-order] [--pretty|--minimize] [--python] [文件]
+        对比两个 JSON 文档，输出 JSON Patch（RFC 6902），有差异时退出码为 1；
+        --ignore-order 忽略数组顺序
+  url encode|decode [文本]
+        URL 编解码；未给出文本时读取标准输入
+  stack [--crlf] [文件]
+        将堆栈字符串重新分行；未给出文件时读取标准输入
+  help  显示本帮助
+";
+
+/// 参数以子命令开头时执行命令行模式，否则返回 `None` 以启动图形界面。
+pub fn run(args: &[String]) -> Option<ExitCode> {
+    let (command, rest) = args.split_first()?;
+    let result = match command.as_str() {
+        "ts" => run_ts(rest),
+        "json" => run_json(rest),
+        "url" => run_url(rest),
+        "stack" => run_stack(rest),
+        "help" | "-h" | "--help" => {
+            print!("{USAGE}");
+            Ok(0)
+        }
+        _ => return None,
+    };
+    let code = result.unwrap_or_else(|e| {
+        eprintln!("handy: {e}");
+        eprint!("{USAGE}");
+        EXIT_USAGE
+    });
+    Some(ExitCode::from(code))
+}
+
+/// `ts` 子命令的参数
+#[derive(Debug, PartialEq)]
+struct TsArgs {
+    unit: TimeUnit,
+    zone: Zone,
+    epoch: Epoch,
+    values: Vec<String>,
+}
+
+fn parse_ts_args(args: &[String]) -> Result<TsArgs, String> {
+    let mut parsed = TsArgs {
+        unit: TimeUnit::Milli,
+        zone: Zone::Local,
+        epoch: Epoch::Unix,
+        values: vec![],
+    };
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--unit" | "-u" => {
+                let value = iter.next().ok_or("--unit 缺少参数")?;
+                parsed.unit = match value.as_str() {
+                    "s" => TimeUnit::Sec,
+                    "ms" => TimeUnit::Milli,
+                    "us" => TimeUnit::Micro,
+                    "ns" => TimeUnit::Nano,
+                    other => return Err(format!("未知的时间单位：{other}")),
+                };
+            }
+            "--tz" => parsed.zone = iter.next().ok_or("--tz 缺少参数")?.parse()?,
+            "--epoch" => parsed.epoch = iter.next().ok_or("--epoch 缺少参数")?.parse()?,
+            flag if is_flag(flag) => return Err(format!("未知选项：{flag}")),
+            value => parsed.values.push(value.to_owned()),
+        }
+    }
+    Ok(parsed)
+}
+
+fn run_ts(args: &[String]) -> Result<u8, String> {
+    let TsArgs {
+        unit,
+        zone,
+        epoch,
+        mut values,
+    } = parse_ts_args(args)?;
+    if values.is_empty() {
+        values = read_input(None)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_owned)
+            .collect();
+    }
+
+    let mut code = 0;
+    let mut stdout = io::stdout().lock();
+    for value in values {
+        if value == "now" {
+            // 时间戳和时间字符串取自同一时刻
+            let now = chrono::Utc::now();
+            let ts = match epoch {
+                Epoch::Unix => timestamp::timestamp_in(now, unit)
+                    .unwrap_or_default()
+                    .to_string(),
+                epoch => epoch.from_instant(now, zone),
+            };
+            let dt = timestamp::format_in(now, zone, unit);
+            writeln!(stdout, "{ts}\t{dt}").map_err(|e| e.to_string())?;
+            continue;
+        }
+        let converted = timestamp::convert_epoch(&value, epoch, unit, zone);
+        if !converted.output.is_empty() {
+            writeln!(stdout, "{}", converted.output).map_err(|e| e.to_string())?;
+        }
+        code = code.max(report(&value, &converted.output, converted.warning));
+    }
+    Ok(code)
+}
+
+/// `json` 子命令的参数，文件路径均借用自 `args`
+#[derive(Debug, Default, PartialEq)]
+struct JsonArgs<'a> {
+    options: json::Options,
+    path: Option<&'a str>,
+    query: Option<&'a str>,
+    diff_with: Option<&'a str>,
+    ignore_order: bool,
+    schema: Option<&'a str>,
+    infer_schema: bool,
+}
+
+fn parse_json_args(args: &[String]) -> Result<JsonArgs<'_>, String> {
+    let mut parsed = JsonArgs::default();
+    let options = &mut parsed.options;
+    let mut iter = args.iter().map(String::as_str);
+    while let Some(arg) = iter.next() {
+        match arg {
+            "--pretty" => options.format = Formatter::Pretty,
+            "--minimize" => options.format = Formatter::Minimize,
+            "--raw" => options.format = Formatter::None,
+            "--canonical" => options.format = Formatter::Canonical,
+            "--indent" => {
+                let indent = iter.next().ok_or("--indent 缺少参数")?;
+                options.indent = indent
+                    .parse()
+                    .map_err(|_| format!("无效的缩进宽度：{indent}"))?;
+            }
+            "--sort-keys" => options.sort_keys = true,
+            "--sort-arrays" => options.sort_arrays = true,
+            "--drop-nulls" => options.drop_nulls = true,
+            "--drop-empty" => options.drop_empty = true,
+            "--ascii" => options.escape_non_ascii = true,
+            "--serialize" => options.conversion = Conversion::Serialize,
+            "--deserialize" => options.conversion = Conversion::Deserialize,
+            "--python" => options.pythonic_style = true,
+            "--to-python" => options.python_output = true,
+            "--types" => options.types = Some(iter.next().ok_or("--types 缺少参数")?.parse()?),
+            "--json5" | "--jsonc" => options.input_mode = InputMode::Json5,
+            "--ndjson" => options.input_mode = InputMode::Ndjson,
+            "--from" => options.source = iter.next().ok_or("--from 缺少参数")?.parse()?,
+            "--to" => options.target = iter.next().ok_or("--to 缺少参数")?.parse()?,
+            "--base64" => options.binary_encoding = BinaryEncoding::Base64,
+            "--query" | "-q" => parsed.query = Some(iter.next().ok_or("--query 缺少参数")?),
+            "--diff" => parsed.diff_with = Some(iter.next().ok_or("--diff 缺少参数")?),
+            "--ignore-order" => parsed.ignore_order = true,
+            "--schema" => parsed.schema = Some(iter.next().ok_or("--schema 缺少参数")?),
+            "--infer-schema" => parsed.infer_schema = true,
+            flag if is_flag(flag) => return Err(format!("未知选项：{flag}")),
+            file => parsed.path = Some(file),
+        }
+    }
+    if options.conversion == Conversion::Serialize {
+        // 与界面一致：序列化后无法格式化 json
+        options.format = Formatter::None;
+    }
+    Ok(parsed)
+}
+
+fn run_json(args: &[String]) -> Result<u8, String> {
+    let JsonArgs {
+        options,
+        path,
+        query,
+        diff_with,
+        ignore_order,
+        schema,
+        infer_schema,
+    } = parse_json_args(args)?;
+
+    // 所有模式都忽略输入末尾的空白，与界面一致
+    let input = read_input(path)?;
+    let input = input.trim_end();
+    if let Some(right) = diff_with {
+        let right = read_input(Some(right))?;
+        return run_diff(input, right.trim_end(), &options, ignore_order);
+    }
+    if let Some(schema) = schema {
+        return run_schema(input, &read_input(Some(schema))?, &options);
+    }
+    if infer_schema {
+        let value = match json::parse(input, &options) {
+            Ok(value) => value,
+            Err(e) => return Ok(report("json", "", Some(e))),
+        };
+        let schema = serde_json::to_string_pretty(&json::infer_schema(&value));
+        println!("{}", schema.map_err(|e| e.to_string())?);
+        return Ok(0);
+    }
+    if let Some(query) = query {
+        return match json::parse(input, &options) {
+            Ok(value) => run_query(&value, query, &options),
+            Err(e) => Ok(report("json", "", Some(e))),
+        };
+    }
+    let processed = json::process(input, &options);
+    if !processed.output.is_empty() {
+        println!("{}", processed.output);
+    }
+    let code = report("json", &processed.output, processed.warning);
+    if let Some(hint) = processed.hint {
+        eprintln!("提示：{hint}");
+    }
+    Ok(code)
+}
+
+fn run_query(value: &Value, query: &str, options: &json::Options) -> Result<u8, String> {
+    let results = match json::query(value, query) {
+        Ok(results) => results,
+        Err(e) => return Ok(report(query, "", Some(e))),
+    };
+    let mut stdout = io::stdout().lock();
+    for mut result in results {
+        // 查询作用于原始数据，整理选项只影响输出
+        json::normalize(&mut result, options);
+        let text = if options.python_output {
+            json::to_python(&result, options.format == Formatter::Pretty)
+        } else {
+            json::to_json(&result, options)
+        };
+        writeln!(stdout, "{text}").map_err(|e| e.to_string())?;
+    }
+    Ok(0)
+}
+
+fn run_diff(
+    left: &str,
+    right: &str,
+    options: &json::Options,
+    ignore_order: bool,
+) -> Result<u8, String> {
+    let left = match json::parse(left, options) {
+        Ok(left) => left,
+        Err(e) => return Ok(report("左侧", "", Some(e))),
+    };
+    let right = match json::parse(right, options) {
+        Ok(right) => right,
+        Err(e) => return Ok(report("右侧", "", Some(e))),
+    };
+    let diff = json::diff(&left, &right, ignore_order);
+    let patch = json::to_patch(&diff);
+    let text = match options.format {
+        Formatter::Minimize => serde_json::to_string(&patch),
+        _ => serde_json::to_string_pretty(&patch),
+    }
+    .map_err(|e| e.to_string())?;
+    println!("{text}");
+    Ok(if diff.is_same() { 0 } else { EXIT_WARNING })
+}
+
+/// 每处不符合 Schema 的地方输出一行，有错误时退出码为 2
+fn run_schema(input: &str, schema: &str, options: &json::Options) -> Result<u8, String> {
+    let schema = match serde_json::from_str(schema) {
+        Ok(schema) => schema,
+        Err(e) => return Ok(report("schema", "", Some(format!("不是有效的 JSON：{e}")))),
+    };
+    let value = match json::parse(input, options) {
+        Ok(value) => value,
+        Err(e) => return Ok(report("json", "", Some(e))),
+    };
+    let violations = match json::validate_schema(&schema, &value) {
+        Ok(violations) => violations,
+        Err(e) => return Ok(report("schema", "", Some(e))),
+    };
+    for violation in &violations {
+        println!(
+            "{}: {}",
+            json::path_from_pointer(&value, &violation.pointer),
+            violation.message
+        );
+    }
+    Ok(if violations.is_empty() {
+        0
+    } else {
+        EXIT_INVALID
+    })
+}
+
+fn run_url(args: &[String]) -> Result<u8, String> {
+    let (direction, rest) = args.split_first().ok_or("缺少 encode 或 decode")?;
+    let conversion = match direction.as_str() {
+        "encode" => url::Conversion::Encode,
+        "decode" => url::Conversion::Decode,
+        other => return Err(format!("未知的转换方向：{other}")),
+    };
+    let input = match rest {
+        [] => strip_newline(read_input(None)?),
+        [text] => text.clone(),
+        _ => return Err("只能给出一段文本".to_owned()),
+    };
+    match url::convert(&input, conversion) {
+        Ok(converted) => {
+            println!("{converted}");
+            Ok(0)
+        }
+        Err(e) => Ok(report("url", "", Some(e))),
+    }
+}
+
+fn run_stack(args: &[String]) -> Result<u8, String> {
+    let mut format = LineFormat::Lf;
+    let mut path = None;
+    for arg in args {
+        match arg.as_str() {
+            "--crlf" => format = LineFormat::Crlf,
+            "--lf" => format = LineFormat::Lf,
+            flag if is_flag(flag) => return Err(format!("未知选项：{flag}")),
+            file => path = Some(file),
+        }
+    }
+    print!("{}", stack::format(&read_input(path)?, format));
+    Ok(0)
+}
+
+/// 将警告输出到标准错误，并返回对应的退出码。
+fn report(input: &str, output: &str, warning: Option<impl ToString>) -> u8 {
+    let Some(warning) = warning else {
+        return 0;
+    };
+    eprintln!("{input}: {}", warning.to_string());
+    if output.is_empty() {
+        EXIT_INVALID
+    } else {
+        EXIT_WARNING
+    }
+}
+
+fn is_flag(arg: &str) -> bool {
+    arg.len() > 1 && arg.starts_with('-') && !arg[1..].starts_with(|c: char| c.is_ascii_digit())
+}
+
+fn read_input(path: Option<&str>) -> Result<String, String> {
+    match path {
+        Some("-") | None => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("读取标准输入失败：{e}"))?;
+            Ok(input)
+        }
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("读取 {path} 失败：{e}")),
+    }
+}
+
+fn strip_newline(mut input: String) -> String {
+    if input.ends_with('\n') {
+        input.pop();
+        if input.ends_with('\r') {
+            input.pop();
+        }
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn ts_args() {
+        let parsed = parse_ts_args(&args(&[
+            "-u", "s", "--tz", "UTC", "--epoch", "gps", "now", "-1",
+        ]));
+        assert_eq!(
+            parsed,
+            Ok(TsArgs {
+                unit: TimeUnit::Sec,
+                zone: Zone::Utc,
+                epoch: Epoch::Gps,
+                values: vec!["now".to_owned(), "-1".to_owned()],
+            })
+        );
+        assert_eq!(
+            parse_ts_args(&[]).map(|parsed| parsed.unit),
+            Ok(TimeUnit::Milli)
+        );
+        assert!(parse_ts_args(&args(&["--unit", "min"])).is_err());
+        assert!(parse_ts_args(&args(&["--tz"])).is_err());
+        assert!(parse_ts_args(&args(&["--verbose"])).is_err());
+    }
+
+    #[test]
+    fn json_args() {
+        let argv = args(&["--minimize", "--sort-keys", "-q", "$.a", "in.json"]);
+        let parsed = parse_json_args(&argv).unwrap();
+        assert_eq!(parsed.options.format, Formatter::Minimize);
+        assert!(parsed.options.sort_keys);
+        assert_eq!(parsed.query, Some("$.a"));
+        assert_eq!(parsed.path, Some("in.json"));
+
+        let argv = args(&["--diff", "b.json", "--ignore-order", "-"]);
+        let parsed = parse_json_args(&argv).unwrap();
+        assert_eq!(
+            (parsed.diff_with, parsed.ignore_order),
+            (Some("b.json"), true)
+        );
+        assert_eq!(parsed.path, Some("-"));
+
+        let argv = args(&["--pretty", "--serialize"]);
+        let parsed = parse_json_args(&argv).unwrap();
+        assert_eq!(parsed.options.format, Formatter::None);
+
+        assert!(parse_json_args(&args(&["--indent", "wide"])).is_err());
+        assert!(parse_json_args(&args(&["--from", "xml"])).is_err());
+        assert!(parse_json_args(&args(&["--schema"])).is_err());
+        assert!(parse_json_args(&args(&["--unknown"])).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use eframe::egui::{self, Button, Event, RichText, TopBottomPanel};
+
+use crate::{
+    app::ToolSlot,
+    engine::{
+        detect::{self, Detection, Kind},
+        stack::LineFormat,
+        timestamp::TimeUnit,
+    },
+    style,
+};
+
+/// 最多给出的建议数
+const MAX_SUGGESTIONS: usize = 3;
+
+/// 识别粘贴的文本，并提示用合适的工具打开
+#[derive(Default)]
+pub(crate) struct PasteDetector {
+    text: String,
+    detections: Vec<Detection>,
+}
+
+/// 用户选择的工具、打开后执行的操作以及要填入的文本
+pub(crate) struct Suggestion {
+    pub(crate) tool: usize,
+    pub(crate) action: Option<&'static str>,
+    pub(crate) input: String,
+}
+
+impl PasteDetector {
+    /// 检查本帧的粘贴事件并在主面板上方显示提示。
+    ///
+    /// 文本粘贴到当前工具的输入框时不再提示。
+    pub(crate) fn show(
+        &mut self,
+        ctx: &egui::Context,
+        tools: &[ToolSlot],
+        visible: &[usize],
+        active_tool: Option<usize>,
+    ) -> Option<Suggestion> {
+        let pasted = ctx.input(|i| {
+            i.events.iter().rev().find_map(|event| match event {
+                Event::Paste(text) => Some(text.clone()),
+                _ => None,
+            })
+        });
+        if let Some(text) = pasted {
+            let focused = ctx.memory(|m| m.focused().is_some());
+            let detections = detect::detect(&text)
+                .into_iter()
+                .filter(|d| find_tool(tools, visible, d.kind).is_some())
+                .collect::<Vec<_>>();
+            let best_is_active = detections
+                .first()
+                .and_then(|d| find_tool(tools, visible, d.kind))
+                .is_some_and(|idx| Some(idx) == active_tool);
+            if !(focused && best_is_active) {
+                self.text = text;
+                self.detections = detections;
+            }
+        }
+        if self.detections.is_empty() {
+            return None;
+        }
+
+        let mut chosen = None;
+        let mut dismissed = false;
+        TopBottomPanel::top("paste_detector").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                let dark_mode = ui.visuals().dark_mode;
+                ui.label(
+                    RichText::new(format!(
+                        "📋 粘贴的内容可能是{}",
+                        self.detections[0].kind.label()
+                    ))
+                    .color(style::prompt_color(dark_mode)),
+                );
+                for detection in self.detections.iter().take(MAX_SUGGESTIONS) {
+                    let Some(idx) = find_tool(tools, visible, detection.kind) else {
+                        continue;
+                    };
+                    let slot = &tools[idx];
+                    let text = format!(
+                        "{} 用{}打开（{}）",
+                        slot.info.icon,
+                        slot.tool.name(),
+                        detection.kind.label()
+                    );
+                    if ui.add(Button::new(text)).clicked() {
+                        chosen = Some(Suggestion {
+                            tool: idx,
+                            action: target(detection.kind).1,
+                            input: self.text.clone(),
+                        });
+                    }
+                }
+                if ui.add(Button::new("✖ 忽略").frame(false)).clicked() {
+                    dismissed = true;
+                }
+            });
+        });
+        if chosen.is_some() || dismissed {
+            self.detections.clear();
+            self.text.clear();
+        }
+        chosen
+    }
+}
+
+fn find_tool(tools: &[ToolSlot], visible: &[usize], kind: Kind) -> Option<usize> {
+    let (id, _) = target(kind);
+    visible
+        .iter()
+        .copied()
+        .find(|&idx| tools[idx].info.id == id)
+}
+
+/// 每种格式对应的工具 id，以及填入文本后执行的操作
+fn target(kind: Kind) -> (&'static str, Option<&'static str>) {
+    match kind {
+        Kind::Timestamp(unit) => (
+            "timestamp",
+            Some(match unit {
+                TimeUnit::Sec => "unit_s",
+                TimeUnit::Milli => "unit_ms",
+                TimeUnit::Micro => "unit_us",
+                TimeUnit::Nano => "unit_ns",
+            }),
+        ),
+        Kind::DateTime => ("timestamp", None),
+        Kind::Json => ("json", Some("json")),
+        Kind::JsonString => ("json", Some("deserialize")),
+        Kind::PythonDict => ("json", Some("python")),
+        Kind::EncodedUrl => ("url", Some("decode")),
+        Kind::EscapedStack(LineFormat::Lf) => ("stack", Some("lf")),
+        Kind::EscapedStack(LineFormat::Crlf) => ("stack", Some("crlf")),
+        Kind::IssueId => ("issue", None),
+        Kind::ExecId => ("taskgraph", None),
+    }
+}
@@ -0,0 +1,156 @@
+//! 打开指定工具并填入输入的链接，如 `#tool=timestamp&input=1700000000123`。
+//!
+//! 网页端从地址的 fragment 或查询参数读取，桌面端从命令行参数读取。
+
+use url::form_urlencoded;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeepLink {
+    /// 工具 id，见 [`crate::ToolInfo::id`]
+    pub tool: String,
+    pub input: Option<String>,
+    /// 填入输入后执行的操作，见 [`crate::ToolItem::actions`]
+    pub action: Option<String>,
+}
+
+impl DeepLink {
+    pub fn new(tool: &str, input: Option<&str>) -> Self {
+        Self {
+            tool: tool.to_owned(),
+            input: input.map(str::to_owned),
+            action: None,
+        }
+    }
+
+    /// 解析链接，可以是完整的 URL，也可以只是 `#...` 或 `?...` 部分。
+    ///
+    /// 同时存在时 fragment 优先于查询参数。
+    pub fn parse(link: &str) -> Option<Self> {
+        let (rest, fragment) = link.split_once('#').unwrap_or((link, ""));
+        let query = rest.split_once('?').map_or(rest, |(_, query)| query);
+        Self::from_pairs(fragment).or_else(|| Self::from_pairs(query))
+    }
+
+    /// 从命令行参数读取，支持 `--tool <id> [--input <text>] [--action <id>]`，
+    /// 或直接给出链接。
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let mut link = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--tool" => link.tool = iter.next()?.clone(),
+                "--input" => link.input = Some(iter.next()?.clone()),
+                "--action" => link.action = Some(iter.next()?.clone()),
+                other if other.contains("tool=") => return Self::parse(other),
+                _ => {}
+            }
+        }
+        (!link.tool.is_empty()).then_some(link)
+    }
+
+    fn from_pairs(pairs: &str) -> Option<Self> {
+        let mut link = Self::default();
+        for (key, value) in form_urlencoded::parse(pairs.as_bytes()) {
+            match key.as_ref() {
+                "tool" => link.tool = value.into_owned(),
+                "input" => link.input = Some(value.into_owned()),
+                "action" => link.action = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        (!link.tool.is_empty()).then_some(link)
+    }
+
+    /// 去掉地址中的查询参数和 fragment，作为 [`Self::to_url`] 的 `base`
+    pub fn base_url(url: &str) -> &str {
+        url.split(['?', '#']).next().unwrap_or_default()
+    }
+
+    /// 生成 fragment 形式的链接，`base` 为网页地址（不含 `#`），可以为空
+    pub fn to_url(&self, base: &str) -> String {
+        let mut pairs = form_urlencoded::Serializer::new(String::new());
+        pairs.append_pair("tool", &self.tool);
+        if let Some(input) = &self.input {
+            pairs.append_pair("input", input);
+        }
+        if let Some(action) = &self.action {
+            pairs.append_pair("action", action);
+        }
+        format!("{base}#{}", pairs.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn round_trips_special_characters() {
+        for input in [
+            "a&b=c",
+            "1 + 1 # 注释",
+            "100%",
+            "%20 已编码",
+            "?x=1&y=2#frag",
+            "中文 😀",
+        ] {
+            let link = DeepLink {
+                tool: "url".to_owned(),
+                input: Some(input.to_owned()),
+                action: Some("decode".to_owned()),
+            };
+            let url = link.to_url("https://example.com/handy/");
+            assert_eq!(DeepLink::parse(&url), Some(link), "{url}");
+        }
+    }
+
+    #[test]
+    fn fragment_wins_over_query() {
+        let link = DeepLink::parse("https://host/?tool=url&input=q#tool=json&input=%7B%7D");
+        assert_eq!(link, Some(DeepLink::new("json", Some("{}"))));
+        let link = DeepLink::parse("https://host/?tool=url&input=a%2Bb#other");
+        assert_eq!(link, Some(DeepLink::new("url", Some("a+b"))));
+        assert_eq!(DeepLink::parse("https://host/#input=1"), None);
+    }
+
+    #[test]
+    fn from_command_line() {
+        let link = DeepLink::from_args(&args(&[
+            "--tool",
+            "timestamp",
+            "--input",
+            "1700000000",
+            "--action",
+            "unit_s",
+        ]));
+        assert_eq!(
+            link,
+            Some(DeepLink {
+                tool: "timestamp".to_owned(),
+                input: Some("1700000000".to_owned()),
+                action: Some("unit_s".to_owned()),
+            })
+        );
+        let link = DeepLink::from_args(&args(&["#tool=json&input=%5B1%5D"]));
+        assert_eq!(link, Some(DeepLink::new("json", Some("[1]"))));
+        assert_eq!(DeepLink::from_args(&args(&["--input", "x"])), None);
+        assert_eq!(DeepLink::from_args(&args(&["--tool"])), None);
+    }
+
+    #[test]
+    fn base_url_drops_query_and_fragment() {
+        assert_eq!(
+            DeepLink::base_url("https://host/app/?a=1#tool=json"),
+            "https://host/app/"
+        );
+        assert_eq!(
+            DeepLink::base_url("https://host/#tool=json"),
+            "https://host/"
+        );
+        assert_eq!(DeepLink::base_url(""), "");
+    }
+}
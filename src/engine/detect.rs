@@ -0,0 +1,220 @@
+//! 猜测一段文本属于哪种格式，用于把粘贴的内容交给合适的工具。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{
+    json, stack,
+    timestamp::{self, TimeUnit},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// 纯数字的时间戳，按位数推断单位
+    Timestamp(TimeUnit),
+    /// 时间字符串，支持的格式见 [`timestamp::parse_datetime`]
+    DateTime,
+    Json,
+    /// 带引号的 JSON 字符串，需要反序列化
+    JsonString,
+    /// Python dict/list 字面量（如 `{'key': None}`）
+    PythonDict,
+    /// 含有百分号编码的 URL 或查询参数
+    EncodedUrl,
+    /// 换行符被转义为 `\n` 或 `\r\n` 的堆栈
+    EscapedStack(stack::LineFormat),
+    /// 纯数字的 issue/event id
+    IssueId,
+    /// UUID 或 32 位十六进制形式的任务执行 id
+    ExecId,
+}
+
+impl Kind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kind::Timestamp(TimeUnit::Sec) => "秒级时间戳",
+            Kind::Timestamp(TimeUnit::Milli) => "毫秒级时间戳",
+            Kind::Timestamp(TimeUnit::Micro) => "微秒级时间戳",
+            Kind::Timestamp(TimeUnit::Nano) => "纳秒级时间戳",
+            Kind::DateTime => "时间字符串",
+            Kind::Json => "JSON",
+            Kind::JsonString => "序列化的 JSON 字符串",
+            Kind::PythonDict => "Python dict",
+            Kind::EncodedUrl => "URL 编码的文本",
+            Kind::EscapedStack(_) => "转义的堆栈字符串",
+            Kind::IssueId => "issue id",
+            Kind::ExecId => "任务执行 id",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Detection {
+    pub kind: Kind,
+    /// 0～100，越大越可信
+    pub confidence: u8,
+}
+
+/// 返回所有可能的格式，按可信度从高到低排列。
+pub fn detect(text: &str) -> Vec<Detection> {
+    let trimmed = text.trim();
+    let mut found = vec![];
+    if trimmed.is_empty() {
+        return found;
+    }
+    let mut push = |kind, confidence| found.push(Detection { kind, confidence });
+
+    if trimmed.bytes().all(|b| b.is_ascii_digit()) {
+        match trimmed.len() {
+            10 => push(Kind::Timestamp(TimeUnit::Sec), 90),
+            13 => push(Kind::Timestamp(TimeUnit::Milli), 95),
+            16 => push(Kind::Timestamp(TimeUnit::Micro), 90),
+            19 => push(Kind::Timestamp(TimeUnit::Nano), 90),
+            9..=20 => {
+                if let Some(unit) = timestamp::unit_for(trimmed) {
+                    push(Kind::Timestamp(unit), 50);
+                }
+            }
+            _ => {}
+        }
+        if trimmed.len() <= 12 {
+            push(Kind::IssueId, if trimmed.len() < 9 { 70 } else { 40 });
+        }
+    }
+
+    if timestamp::parse_datetime(trimmed).is_some() {
+        push(Kind::DateTime, 90);
+    }
+
+    if trimmed.starts_with(['{', '[']) {
+        if serde_json::from_str::<serde_json::Value>(trimmed).is_ok() {
+            push(Kind::Json, 95);
+        } else if is_python_literal(trimmed) {
+            push(Kind::PythonDict, 85);
+        } else {
+            // 可能是不完整的 JSON，仍交给 JSON 工具查看错误
+            push(Kind::Json, 30);
+        }
+    } else if trimmed.starts_with('"')
+        && serde_json::from_str::<String>(trimmed)
+            .is_ok_and(|s| serde_json::from_str::<serde_json::Value>(&s).is_ok())
+    {
+        push(Kind::JsonString, 90);
+    }
+
+    if has_percent_escape(trimmed)
+        && super::url::decode(trimmed).is_ok_and(|decoded| decoded != trimmed)
+    {
+        push(Kind::EncodedUrl, 80);
+    }
+
+    if let Some(format) = escaped_line_format(trimmed) {
+        let lines = stack::split(trimmed, format);
+        let confidence = if lines.iter().any(|line| line.is_code) {
+            85
+        } else {
+            45
+        };
+        push(Kind::EscapedStack(format), confidence);
+    }
+
+    if is_exec_id(trimmed) {
+        push(Kind::ExecId, 60);
+    }
+
+    found.sort_by_key(|d| std::cmp::Reverse(d.confidence));
+    found
+}
+
+fn is_python_literal(text: &str) -> bool {
+    json::parse_python(text).is_ok()
+}
+
+fn has_percent_escape(text: &str) -> bool {
+    static ESCAPE: Lazy<Regex> = Lazy::new(|| Regex::new(r"%[0-9A-Fa-f]{2}").unwrap());
+    ESCAPE.is_match(text)
+}
+
+/// 文本中出现转义换行符时，返回对应的格式
+fn escaped_line_format(text: &str) -> Option<stack::LineFormat> {
+    if text.contains("\\r\\n") {
+        Some(stack::LineFormat::Crlf)
+    } else if text.contains("\\n") {
+        Some(stack::LineFormat::Lf)
+    } else {
+        None
+    }
+}
+
+fn is_exec_id(text: &str) -> bool {
+    static EXEC_ID: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^([[:xdigit:]]{8}(-[[:xdigit:]]{4}){3}-[[:xdigit:]]{12}|[[:xdigit:]]{32})$")
+            .unwrap()
+    });
+    EXEC_ID.is_match(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn best(text: &str) -> Option<Kind> {
+        detect(text).first().map(|d| d.kind)
+    }
+
+    #[test]
+    fn timestamps_by_length() {
+        assert_eq!(best("1700000000"), Some(Kind::Timestamp(TimeUnit::Sec)));
+        // 与转换时一样，超过 10 位按毫秒处理
+        assert!(
+            detect("17000000001")
+                .iter()
+                .any(|d| d.kind == Kind::Timestamp(TimeUnit::Milli))
+        );
+        assert_eq!(
+            best("1700000000123"),
+            Some(Kind::Timestamp(TimeUnit::Milli))
+        );
+        assert_eq!(
+            best("1700000000123456789"),
+            Some(Kind::Timestamp(TimeUnit::Nano))
+        );
+        assert_eq!(best("12345"), Some(Kind::IssueId));
+    }
+
+    #[test]
+    fn datetime() {
+        assert_eq!(best("2023-11-14 22:13:20"), Some(Kind::DateTime));
+        assert_eq!(best("2023-11-14 22:13:20.123"), Some(Kind::DateTime));
+    }
+
+    #[test]
+    fn json_kinds() {
+        assert_eq!(best(r#"{"a": [1, 2]}"#), Some(Kind::Json));
+        assert_eq!(best("{'a': None}"), Some(Kind::PythonDict));
+        assert_eq!(best(r#""{\"a\":1}""#), Some(Kind::JsonString));
+        let partial = detect(r#"{"a": "#);
+        assert_eq!(partial[0].kind, Kind::Json);
+        assert!(partial[0].confidence < 50);
+    }
+
+    #[test]
+    fn url_stack_and_exec_id() {
+        assert_eq!(best("a%20b%3Dc"), Some(Kind::EncodedUrl));
+        assert_eq!(
+            best("panic\\r\\n\\tat main.rs:12"),
+            Some(Kind::EscapedStack(stack::LineFormat::Crlf))
+        );
+        assert_eq!(
+            best("0f8fad5b-d9cb-469f-a165-70867728950e"),
+            Some(Kind::ExecId)
+        );
+        assert_eq!(best("0f8fad5bd9cb469fa16570867728950e"), Some(Kind::ExecId));
+    }
+
+    #[test]
+    fn nothing_detected() {
+        assert!(detect("  ").is_empty());
+        assert!(detect("hello world").is_empty());
+    }
+}
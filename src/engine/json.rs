@@ -0,0 +1,560 @@
+//! JSON 字符串的序列化、反序列化与格式化。
+
+use serde::{Deserialize, Serialize};
+
+use serde_json::Value;
+
+mod codegen;
+mod diff;
+mod edit;
+mod formats;
+mod json5;
+mod normalize;
+mod python;
+mod query;
+mod schema;
+mod syntax;
+
+pub use codegen::{ALL_LANGUAGES, Language, generate_types};
+pub use diff::{Change, ChangeKind, Diff, diff, escape_token, to_patch};
+pub use edit::{Edit, apply_edit};
+pub use formats::{ALL_FORMATS, BinaryEncoding, DataFormat, Lossy, decode, encode};
+pub use json5::parse_json5;
+pub use normalize::{escape_non_ascii, normalize, to_canonical};
+pub use python::{parse_python, to_python};
+pub use query::{QueryError, path_from_pointer, query};
+pub use schema::{Violation, infer_schema, validate_schema};
+pub use syntax::SyntaxError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Formatter {
+    None,
+    Pretty,
+    Minimize,
+    /// RFC 8785（JCS）规范化，用于对比和计算哈希
+    Canonical,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Conversion {
+    None,
+    Serialize,
+    Deserialize,
+}
+
+/// 输入的语法
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputMode {
+    Json,
+    /// JSON5 与 JSONC，允许注释、末尾逗号和不加引号的键等
+    Json5,
+    /// 每行一个 JSON 文档，结果合并为数组
+    Ndjson,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Options {
+    /// 源格式，不是 JSON 时忽略 `input_mode`、`conversion` 和 `pythonic_style`
+    pub source: DataFormat,
+    /// 目标格式，不是 JSON 时忽略 `format`、`python_output` 和 `types`
+    pub target: DataFormat,
+    /// MessagePack 和 CBOR 的文本表示
+    pub binary_encoding: BinaryEncoding,
+    pub input_mode: InputMode,
+    pub format: Formatter,
+    pub conversion: Conversion,
+    /// 输入为 Python dict 风格（如 `{'key': None}`）
+    pub pythonic_style: bool,
+    /// 输出 Python 字面量而不是 JSON
+    pub python_output: bool,
+    /// 输出从结果推断的类型定义而不是 JSON，优先于 `python_output`
+    pub types: Option<Language>,
+    /// 展开时缩进的空格数
+    pub indent: usize,
+    /// 递归按键排序
+    pub sort_keys: bool,
+    /// 元素都是标量的数组按值排序
+    pub sort_arrays: bool,
+    /// 删除对象中值为 null 的键
+    pub drop_nulls: bool,
+    /// 删除对象中值为空对象或空数组的键
+    pub drop_empty: bool,
+    /// 非 ASCII 字符转义为 `\uXXXX`，规范化输出时不转义
+    pub escape_non_ascii: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            source: DataFormat::Json,
+            target: DataFormat::Json,
+            binary_encoding: BinaryEncoding::Hex,
+            input_mode: InputMode::Json,
+            format: Formatter::Pretty,
+            conversion: Conversion::None,
+            pythonic_style: false,
+            python_output: false,
+            types: None,
+            indent: 2,
+            sort_keys: false,
+            sort_arrays: false,
+            drop_nulls: false,
+            drop_empty: false,
+            escape_non_ascii: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Processed {
+    pub output: String,
+    /// 输出为展开的 JSON，可以用树形视图展示
+    pub pretty: bool,
+    pub warning: Option<String>,
+    /// 解析错误的位置，行列号对应输入文本；经过序列化或反序列化时为 `None`
+    pub error: Option<SyntaxError>,
+    /// 常见错误的修改建议
+    pub hint: Option<&'static str>,
+}
+
+/// 先按 `conversion` 处理字符串，再按 `format` 格式化。
+///
+/// 格式化失败时仍返回转换后的文本，并在 `warning` 中给出解析错误。
+pub fn process(input: &str, options: &Options) -> Processed {
+    if options.source != DataFormat::Json || options.target != DataFormat::Json {
+        return process_formats(input, options);
+    }
+    if options.input_mode == InputMode::Ndjson {
+        return process_ndjson(input, options);
+    }
+    let converted = match convert(input, options) {
+        Ok(converted) => converted,
+        Err(e) => {
+            return failure(
+                input,
+                options.conversion == Conversion::None,
+                e,
+                String::new(),
+            );
+        }
+    };
+
+    let mut value = match serde_json::from_str::<Value>(&converted) {
+        Ok(value) => value,
+        Err(e) => {
+            let error = SyntaxError::from_serde(&converted, &e);
+            return failure(
+                input,
+                options.conversion == Conversion::None,
+                error,
+                converted,
+            );
+        }
+    };
+    normalize(&mut value, options);
+    if options.format == Formatter::None
+        && !reshapes(options)
+        && options.types.is_none()
+        && !options.python_output
+    {
+        return Processed {
+            output: converted,
+            ..Default::default()
+        };
+    }
+    render(&value, options)
+}
+
+/// 把解析并规范化后的文档写为 `options` 指定的输出：类型定义、Python 字面量、JSON 或其他格式。
+///
+/// 与 [`process`] 的输出一致，但 `Formatter::None` 时无法保留原文，输出紧凑格式
+pub fn render(value: &Value, options: &Options) -> Processed {
+    render_with(value, options, Lossy::default())
+}
+
+/// `lossy` 为解析时已经记录的有损转换
+fn render_with(value: &Value, options: &Options, mut lossy: Lossy) -> Processed {
+    let json_target = options.target == DataFormat::Json;
+    let pretty = options.format == Formatter::Pretty;
+    let output = if let Some(language) = options.types.filter(|_| json_target) {
+        Ok(generate_types(value, language))
+    } else if json_target && options.python_output {
+        Ok(to_python(value, pretty))
+    } else if json_target
+        && options.source == DataFormat::Json
+        && options.input_mode == InputMode::Ndjson
+        && options.format == Formatter::None
+        && let Value::Array(values) = value
+    {
+        // 仍按每行一个文档输出
+        Ok(values
+            .iter()
+            .map(|value| to_json(value, options))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    } else if json_target {
+        Ok(to_json(value, options))
+    } else {
+        encode(
+            value,
+            options.target,
+            options.binary_encoding,
+            pretty,
+            &mut lossy,
+        )
+    };
+    match output {
+        Ok(output) => Processed {
+            output,
+            pretty: json_target && pretty && !options.python_output && options.types.is_none(),
+            warning: (!lossy.is_empty()).then(|| lossy.to_string()),
+            ..Default::default()
+        },
+        Err(e) => Processed {
+            warning: Some(e),
+            ..Default::default()
+        },
+    }
+}
+
+/// 按 `format`、`indent` 和 `escape_non_ascii` 输出 JSON，`Formatter::None` 时输出紧凑格式
+pub fn to_json(value: &Value, options: &Options) -> String {
+    if options.format == Formatter::Canonical {
+        return to_canonical(value);
+    }
+    // `Value` 总能序列化为字符串
+    let output = if options.format == Formatter::Pretty {
+        let indent = " ".repeat(options.indent);
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        let mut buffer = vec![];
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+        value.serialize(&mut serializer).unwrap_or_default();
+        String::from_utf8(buffer).unwrap_or_default()
+    } else {
+        value.to_string()
+    };
+    if options.escape_non_ascii {
+        escape_non_ascii(&output)
+    } else {
+        output
+    }
+}
+
+/// 是否有会改变文档内容或写法的选项，此时 `Formatter::None` 不能原样输出
+fn reshapes(options: &Options) -> bool {
+    options.sort_keys
+        || options.sort_arrays
+        || options.drop_nulls
+        || options.drop_empty
+        || options.escape_non_ascii
+}
+
+/// 解析失败的结果，`located` 时错误位置对应输入，附带错误位置和修改建议
+fn failure(input: &str, located: bool, error: SyntaxError, output: String) -> Processed {
+    Processed {
+        output,
+        pretty: false,
+        warning: Some(error.to_string()),
+        hint: located.then(|| error.hint(input)).flatten(),
+        error: located.then_some(error),
+    }
+}
+
+/// 源格式或目标格式不是 JSON 时，先解析为 [`Value`] 再写为目标格式
+fn process_formats(input: &str, options: &Options) -> Processed {
+    let mut lossy = Lossy::default();
+    let mut value = match parse_source(input, options, &mut lossy) {
+        Ok(value) => value,
+        Err(e) => {
+            let json = options.source == DataFormat::Json;
+            let located = if json {
+                options.conversion == Conversion::None
+            } else {
+                !options.source.is_binary()
+            };
+            let mut failed = failure(input, located, e, String::new());
+            if !json {
+                // 修改建议只针对 JSON
+                failed.hint = None;
+            }
+            return failed;
+        }
+    };
+    normalize(&mut value, options);
+    render_with(&value, options, lossy)
+}
+
+/// NDJSON 逐行解析，跳过出错的行，所有错误合并到 `warning` 中
+fn process_ndjson(input: &str, options: &Options) -> Processed {
+    let (mut values, errors) = parse_ndjson(input, options.pythonic_style);
+    for value in &mut values {
+        normalize(value, options);
+    }
+    let warning = (!errors.is_empty()).then(|| {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    // 第一个出错的行用于定位
+    let error = errors.into_iter().next();
+    let hint = error.as_ref().and_then(|e| e.hint(input));
+    if values.is_empty() {
+        return Processed {
+            warning,
+            error,
+            hint,
+            ..Default::default()
+        };
+    }
+    Processed {
+        warning,
+        error,
+        hint,
+        ..render(&Value::Array(values), options)
+    }
+}
+
+/// 逐行解析 NDJSON，忽略空行。返回解析成功的值，以及每个出错行的错误
+pub fn parse_ndjson(input: &str, pythonic_style: bool) -> (Vec<Value>, Vec<SyntaxError>) {
+    let mut values = vec![];
+    let mut errors = vec![];
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let parsed = if pythonic_style {
+            parse_python(line)
+        } else {
+            serde_json::from_str(line).map_err(|e| SyntaxError::from_serde(line, &e))
+        };
+        match parsed {
+            Ok(value) => values.push(value),
+            Err(e) => errors.push(SyntaxError {
+                line: index + 1,
+                ..e
+            }),
+        }
+    }
+    (values, errors)
+}
+
+/// 与 [`process`] 相同的方式解析输入，忽略 `format`、`target` 和 [`normalize`] 相关的选项
+pub fn parse(input: &str, options: &Options) -> Result<Value, String> {
+    parse_source(input, options, &mut Lossy::default()).map_err(|e| e.to_string())
+}
+
+fn parse_source(input: &str, options: &Options, lossy: &mut Lossy) -> Result<Value, SyntaxError> {
+    if options.source != DataFormat::Json {
+        return decode(input, options.source, options.binary_encoding, lossy);
+    }
+    if options.input_mode == InputMode::Ndjson {
+        let (values, errors) = parse_ndjson(input, options.pythonic_style);
+        return match errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(Value::Array(values)),
+        };
+    }
+    let converted = convert(input, options)?;
+    serde_json::from_str(&converted).map_err(|e| SyntaxError::from_serde(&converted, &e))
+}
+
+/// 按 `pythonic_style`、`conversion` 和 `input_mode` 预处理输入
+fn convert(input: &str, options: &Options) -> Result<String, SyntaxError> {
+    // `Value` 总能序列化为字符串
+    let input = if options.pythonic_style {
+        parse_python(input)?.to_string()
+    } else {
+        input.to_owned()
+    };
+    let converted = match options.conversion {
+        Conversion::Deserialize => serde_json::from_str::<String>(&input)
+            .map_err(|e| SyntaxError::from_serde(&input, &e))?,
+        Conversion::Serialize => Value::String(input).to_string(),
+        Conversion::None => input,
+    };
+    match options.input_mode {
+        InputMode::Json5 => Ok(parse_json5(&converted)?.to_string()),
+        _ => Ok(converted),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(format: Formatter, conversion: Conversion) -> Options {
+        Options {
+            format,
+            conversion,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pretty_and_minimize() {
+        let input = r#"{"a": [1, 2]}"#;
+        let pretty = process(input, &Options::default());
+        assert_eq!(pretty.output, "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+        assert!(pretty.pretty);
+        let minimized = process(input, &options(Formatter::Minimize, Conversion::None));
+        assert_eq!(minimized.output, r#"{"a":[1,2]}"#);
+        assert!(!minimized.pretty);
+    }
+
+    #[test]
+    fn keys_keep_input_order() {
+        let minimized = process(
+            r#"{"b": 1, "a": {"z": 2, "y": 3}}"#,
+            &options(Formatter::Minimize, Conversion::None),
+        );
+        assert_eq!(minimized.output, r#"{"b":1,"a":{"z":2,"y":3}}"#);
+    }
+
+    #[test]
+    fn serialize_and_deserialize() {
+        let serialized = process(
+            r#"{"a":1}"#,
+            &options(Formatter::None, Conversion::Serialize),
+        );
+        assert_eq!(serialized.output, r#""{\"a\":1}""#);
+        let deserialized = process(
+            r#""{\"a\":1}""#,
+            &options(Formatter::Minimize, Conversion::Deserialize),
+        );
+        assert_eq!(deserialized.output, r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn invalid_json_keeps_text() {
+        let processed = process("{a: 1}", &options(Formatter::None, Conversion::None));
+        assert_eq!(processed.output, "{a: 1}");
+        assert!(processed.warning.is_some());
+        let error = processed.error.unwrap();
+        assert_eq!((error.line, error.column), (1, 2));
+        assert!(processed.hint.is_some());
+        // 反序列化后的位置与输入对不上，不给出位置
+        let processed = process(
+            r#""{a: 1}""#,
+            &options(Formatter::None, Conversion::Deserialize),
+        );
+        assert!(processed.warning.is_some() && processed.error.is_none());
+    }
+
+    #[test]
+    fn ndjson_lines() {
+        let options = Options {
+            input_mode: InputMode::Ndjson,
+            format: Formatter::Minimize,
+            ..Default::default()
+        };
+        let processed = process("{\"a\":1}\n\n[2]\n{bad}\n", &options);
+        assert_eq!(processed.output, r#"[{"a":1},[2]]"#);
+        assert!(processed.warning.unwrap().starts_with("第 4 行"));
+        let processed = process(
+            "{\"a\": 1}\n[2]",
+            &Options {
+                format: Formatter::None,
+                ..options.clone()
+            },
+        );
+        assert_eq!(processed.output, "{\"a\":1}\n[2]");
+    }
+
+    #[test]
+    fn json5_input() {
+        let options = Options {
+            input_mode: InputMode::Json5,
+            format: Formatter::Minimize,
+            ..Default::default()
+        };
+        assert_eq!(process("{a: 1, // x\n}", &options).output, r#"{"a":1}"#);
+    }
+
+    #[test]
+    fn output_options() {
+        let input = r#"{"b": "中", "a": null}"#;
+        let output = |options: Options| process(input, &options).output;
+        assert_eq!(
+            output(Options {
+                indent: 4,
+                ..Default::default()
+            }),
+            "{\n    \"b\": \"中\",\n    \"a\": null\n}"
+        );
+        assert_eq!(
+            output(Options {
+                format: Formatter::Canonical,
+                ..Default::default()
+            }),
+            r#"{"a":null,"b":"中"}"#
+        );
+        assert_eq!(
+            output(Options {
+                format: Formatter::None,
+                drop_nulls: true,
+                escape_non_ascii: true,
+                ..Default::default()
+            }),
+            r#"{"b":"\u4e2d"}"#
+        );
+        // 没有会改变内容的选项时原样输出
+        assert_eq!(output(options(Formatter::None, Conversion::None)), input);
+    }
+
+    #[test]
+    fn render_matches_process() {
+        let input = "{\"b\": [1, null], \"a\": \"中\"}";
+        let value = serde_json::from_str::<Value>(input).unwrap();
+        for options in [
+            Options {
+                indent: 4,
+                drop_nulls: true,
+                ..Default::default()
+            },
+            Options {
+                python_output: true,
+                ..Default::default()
+            },
+            Options {
+                types: Some(Language::TypeScript),
+                ..Default::default()
+            },
+            Options {
+                target: DataFormat::Yaml,
+                ..Default::default()
+            },
+            options(Formatter::Canonical, Conversion::None),
+        ] {
+            let mut normalized = value.clone();
+            normalize(&mut normalized, &options);
+            assert_eq!(render(&normalized, &options), process(input, &options));
+            // 解析结果保持原样，只在输出时整理
+            assert_eq!(parse(input, &options), Ok(value.clone()));
+        }
+        let options = Options {
+            input_mode: InputMode::Ndjson,
+            format: Formatter::None,
+            ..Default::default()
+        };
+        assert_eq!(
+            render(&serde_json::json!([{"a": 1}, [2]]), &options).output,
+            "{\"a\":1}\n[2]"
+        );
+    }
+
+    #[test]
+    fn pythonic_style() {
+        let processed = process(
+            "{'a': None}",
+            &Options {
+                format: Formatter::Minimize,
+                pythonic_style: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(processed.output, r#"{"a":null}"#);
+    }
+}
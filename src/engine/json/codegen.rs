@@ -0,0 +1,541 @@
+//! 从 JSON 示例生成 Rust、TypeScript 和 Python 类型定义。
+//!
+//! 多个示例合并推断：并非每个示例都有的键生成为可选字段，出现过 `null` 的值生成为可空类型，
+//! 同一位置出现多种类型时生成联合类型（Rust 中为 `serde_json::Value`）。
+
+use std::{collections::HashSet, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::schema::{ObjectShape, Shape};
+
+/// 最外层类型的名称
+const ROOT_NAME: &str = "Root";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    /// 带 serde 注解的结构体
+    Rust,
+    TypeScript,
+    /// dataclass
+    Python,
+}
+
+pub const ALL_LANGUAGES: [Language; 3] = [Language::Rust, Language::TypeScript, Language::Python];
+
+impl Language {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Rust => "Rust",
+            Language::TypeScript => "TypeScript",
+            Language::Python => "Python",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Ok(Language::Rust),
+            "typescript" | "ts" => Ok(Language::TypeScript),
+            "python" | "py" => Ok(Language::Python),
+            _ => Err(format!("未知的语言：{s}")),
+        }
+    }
+}
+
+/// 生成类型定义。`sample` 为非空数组时，每个元素作为一个示例
+pub fn generate_types(sample: &Value, language: Language) -> String {
+    let mut shape = Shape::default();
+    match sample {
+        Value::Array(samples) if !samples.is_empty() => samples.iter().for_each(|s| shape.add(s)),
+        sample => shape.add(sample),
+    }
+    let mut generator = Generator {
+        language,
+        names: HashSet::new(),
+        types: vec![],
+    };
+    if language == Language::Python {
+        // 避免与 typing 中的名称冲突
+        for name in ["Any", "Dict", "List", "Optional", "Union"] {
+            generator.names.insert(name.to_owned());
+        }
+    }
+    // 最外层不只是对象时，用 `Root` 作为类型别名，对象另取名称
+    let mut rest = shape.clone();
+    if rest.object.take().is_none() || !rest.is_empty() {
+        generator.names.insert(ROOT_NAME.to_owned());
+    }
+    let root = generator.type_of(&shape, ROOT_NAME, "");
+    generator.render(&root)
+}
+
+/// 与语言无关的类型
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Ty {
+    /// 没有示例，或无法确定
+    Any,
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str,
+    List(Box<Ty>),
+    /// 没有键的对象
+    Map,
+    Named(String),
+    Union(Vec<Ty>),
+    Nullable(Box<Ty>),
+}
+
+struct TypeDef {
+    name: String,
+    fields: Vec<Field>,
+}
+
+struct Field {
+    key: String,
+    ty: Ty,
+    /// 不是每个示例都有这个键
+    optional: bool,
+}
+
+struct Generator {
+    language: Language,
+    names: HashSet<String>,
+    /// 按先序排列，外层类型在前
+    types: Vec<TypeDef>,
+}
+
+impl Generator {
+    fn type_of(&mut self, shape: &Shape, hint: &str, parent: &str) -> Ty {
+        let mut kinds = vec![];
+        if let Some(object) = &shape.object {
+            kinds.push(if object.properties.is_empty() {
+                Ty::Map
+            } else {
+                Ty::Named(self.define(object, hint, parent))
+            });
+        }
+        if let Some(items) = &shape.array {
+            kinds.push(Ty::List(Box::new(if items.is_empty() {
+                Ty::Any
+            } else {
+                self.type_of(items, &format!("{hint}Item"), parent)
+            })));
+        }
+        if shape.string.is_some() {
+            kinds.push(Ty::Str);
+        }
+        if shape.number {
+            kinds.push(Ty::Float);
+        } else if shape.integer {
+            kinds.push(Ty::Int);
+        }
+        if shape.boolean {
+            kinds.push(Ty::Bool);
+        }
+        let ty = match kinds.len() {
+            0 if shape.null => return Ty::Null,
+            0 => Ty::Any,
+            1 => kinds.remove(0),
+            _ => Ty::Union(kinds),
+        };
+        if shape.null {
+            Ty::Nullable(Box::new(ty))
+        } else {
+            ty
+        }
+    }
+
+    /// 为对象定义一个类型，返回类型名
+    fn define(&mut self, object: &ObjectShape, hint: &str, parent: &str) -> String {
+        let name = self.unique_name(hint, parent);
+        let index = self.types.len();
+        self.types.push(TypeDef {
+            name: name.clone(),
+            fields: vec![],
+        });
+        let fields = object
+            .properties
+            .iter()
+            .map(|(key, (shape, _))| Field {
+                key: key.clone(),
+                ty: self.type_of(shape, &pascal_case(key), &name),
+                optional: !object.is_required(key),
+            })
+            .collect();
+        self.types[index].fields = fields;
+        name
+    }
+
+    /// 名称冲突时先加上外层类型名作为前缀，仍冲突时加序号
+    fn unique_name(&mut self, hint: &str, parent: &str) -> String {
+        let candidates = [hint.to_owned(), format!("{parent}{hint}")];
+        let name = candidates
+            .into_iter()
+            .find(|name| !self.names.contains(name))
+            .unwrap_or_else(|| {
+                (2..)
+                    .map(|n| format!("{hint}{n}"))
+                    .find(|name| !self.names.contains(name))
+                    .unwrap_or_default()
+            });
+        self.names.insert(name.clone());
+        name
+    }
+
+    fn render(&self, root: &Ty) -> String {
+        match self.language {
+            Language::Rust => self.render_rust(root),
+            Language::TypeScript => self.render_typescript(root),
+            Language::Python => self.render_python(root),
+        }
+    }
+
+    fn render_rust(&self, root: &Ty) -> String {
+        let mut out = String::new();
+        if !self.types.is_empty() {
+            out.push_str("use serde::{Deserialize, Serialize};\n");
+        }
+        if !matches!(root, Ty::Named(_)) {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("pub type {ROOT_NAME} = {};\n", rust_type(root)));
+        }
+        for def in &self.types {
+            out.push_str("\n#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+            out.push_str(&format!("pub struct {} {{\n", def.name));
+            for field in &def.fields {
+                let ident = rust_ident(&field.key);
+                let mut attributes = vec![];
+                if ident.trim_start_matches("r#") != field.key {
+                    attributes.push(format!("rename = {}", quote(&field.key)));
+                }
+                let mut ty = rust_type(&field.ty);
+                if field.optional {
+                    if !matches!(field.ty, Ty::Nullable(_) | Ty::Null) {
+                        ty = format!("Option<{ty}>");
+                    }
+                    attributes.push("skip_serializing_if = \"Option::is_none\"".to_owned());
+                }
+                if !attributes.is_empty() {
+                    out.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+                }
+                out.push_str(&format!("    pub {ident}: {ty},\n"));
+            }
+            out.push_str("}\n");
+        }
+        out
+    }
+
+    fn render_typescript(&self, root: &Ty) -> String {
+        let mut out = String::new();
+        if !matches!(root, Ty::Named(_)) {
+            out.push_str(&format!("export type {ROOT_NAME} = {};\n", ts_type(root)));
+        }
+        for def in &self.types {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(&format!("export interface {} {{\n", def.name));
+            for field in &def.fields {
+                let key = if is_identifier(&field.key) {
+                    field.key.clone()
+                } else {
+                    quote(&field.key)
+                };
+                let optional = if field.optional { "?" } else { "" };
+                out.push_str(&format!("  {key}{optional}: {};\n", ts_type(&field.ty)));
+            }
+            out.push_str("}\n");
+        }
+        out
+    }
+
+    fn render_python(&self, root: &Ty) -> String {
+        let mut body = String::new();
+        let mut imports = HashSet::new();
+        // 先定义内层类型，注解中才能引用
+        for def in self.types.iter().rev() {
+            body.push_str(&format!("\n\n@dataclass\nclass {}:\n", def.name));
+            if def.fields.is_empty() {
+                body.push_str("    pass\n");
+            }
+            // 有默认值的字段必须在后面
+            let (optional, required): (Vec<_>, Vec<_>) =
+                def.fields.iter().partition(|field| field.optional);
+            for field in required.into_iter().chain(optional) {
+                let ident = python_ident(&field.key);
+                if ident != field.key {
+                    body.push_str(&format!("    # JSON 中的键：{}\n", quote(&field.key)));
+                }
+                let ty = if field.optional && !matches!(field.ty, Ty::Nullable(_) | Ty::Null) {
+                    Ty::Nullable(Box::new(field.ty.clone()))
+                } else {
+                    field.ty.clone()
+                };
+                let default = if field.optional { " = None" } else { "" };
+                body.push_str(&format!(
+                    "    {ident}: {}{default}\n",
+                    python_type(&ty, &mut imports)
+                ));
+            }
+        }
+        if !matches!(root, Ty::Named(_)) {
+            body.push_str(&format!(
+                "\n\n{ROOT_NAME} = {}\n",
+                python_type(root, &mut imports)
+            ));
+        }
+
+        let mut out = String::new();
+        if !self.types.is_empty() {
+            out.push_str("from dataclasses import dataclass\n");
+        }
+        let mut imports = imports.into_iter().collect::<Vec<_>>();
+        imports.sort();
+        if !imports.is_empty() {
+            out.push_str(&format!("from typing import {}\n", imports.join(", ")));
+        }
+        out.push_str(&body);
+        out.trim_start().to_owned()
+    }
+}
+
+fn rust_type(ty: &Ty) -> String {
+    match ty {
+        Ty::Any | Ty::Union(_) => "serde_json::Value".to_owned(),
+        Ty::Null => "Option<serde_json::Value>".to_owned(),
+        Ty::Bool => "bool".to_owned(),
+        Ty::Int => "i64".to_owned(),
+        Ty::Float => "f64".to_owned(),
+        Ty::Str => "String".to_owned(),
+        Ty::List(item) => format!("Vec<{}>", rust_type(item)),
+        Ty::Map => "serde_json::Map<String, serde_json::Value>".to_owned(),
+        Ty::Named(name) => name.clone(),
+        Ty::Nullable(inner) => format!("Option<{}>", rust_type(inner)),
+    }
+}
+
+fn ts_type(ty: &Ty) -> String {
+    match ty {
+        Ty::Any => "unknown".to_owned(),
+        Ty::Null => "null".to_owned(),
+        Ty::Bool => "boolean".to_owned(),
+        Ty::Int | Ty::Float => "number".to_owned(),
+        Ty::Str => "string".to_owned(),
+        Ty::List(item) => match **item {
+            Ty::Union(_) | Ty::Nullable(_) => format!("({})[]", ts_type(item)),
+            _ => format!("{}[]", ts_type(item)),
+        },
+        Ty::Map => "Record<string, unknown>".to_owned(),
+        Ty::Named(name) => name.clone(),
+        Ty::Union(kinds) => kinds.iter().map(ts_type).collect::<Vec<_>>().join(" | "),
+        Ty::Nullable(inner) => format!("{} | null", ts_type(inner)),
+    }
+}
+
+fn python_type(ty: &Ty, imports: &mut HashSet<&'static str>) -> String {
+    match ty {
+        Ty::Any => {
+            imports.insert("Any");
+            "Any".to_owned()
+        }
+        Ty::Null => {
+            imports.insert("Any");
+            imports.insert("Optional");
+            "Optional[Any]".to_owned()
+        }
+        Ty::Bool => "bool".to_owned(),
+        Ty::Int => "int".to_owned(),
+        Ty::Float => "float".to_owned(),
+        Ty::Str => "str".to_owned(),
+        Ty::List(item) => {
+            imports.insert("List");
+            format!("List[{}]", python_type(item, imports))
+        }
+        Ty::Map => {
+            imports.insert("Any");
+            imports.insert("Dict");
+            "Dict[str, Any]".to_owned()
+        }
+        Ty::Named(name) => name.clone(),
+        Ty::Union(kinds) => {
+            imports.insert("Union");
+            let kinds = kinds
+                .iter()
+                .map(|kind| python_type(kind, imports))
+                .collect::<Vec<_>>();
+            format!("Union[{}]", kinds.join(", "))
+        }
+        Ty::Nullable(inner) => {
+            imports.insert("Optional");
+            format!("Optional[{}]", python_type(inner, imports))
+        }
+    }
+}
+
+/// 键转为类型名，如 `user_info` 和 `userInfo` 都转为 `UserInfo`
+fn pascal_case(key: &str) -> String {
+    let name = key
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    match name.chars().next() {
+        None => "Item".to_owned(),
+        Some(first) if !first.is_alphabetic() => format!("T{name}"),
+        Some(_) => name,
+    }
+}
+
+/// 键转为 snake_case 的字段名，`type` 按惯例写作 `ty`，其他关键字使用原始标识符
+fn rust_ident(key: &str) -> String {
+    let mut ident = String::new();
+    let mut previous_lower = false;
+    for c in key.chars() {
+        if c.is_uppercase() {
+            if previous_lower {
+                ident.push('_');
+            }
+            ident.extend(c.to_lowercase());
+            previous_lower = false;
+        } else if c.is_alphanumeric() {
+            ident.push(c);
+            previous_lower = true;
+        } else {
+            if !ident.ends_with('_') {
+                ident.push('_');
+            }
+            previous_lower = false;
+        }
+    }
+    let ident = ident.trim_matches('_').to_owned();
+    match ident.as_str() {
+        "" => "field".to_owned(),
+        "type" => "ty".to_owned(),
+        // 不能作为原始标识符
+        "self" | "super" | "crate" | "Self" => format!("{ident}_"),
+        _ if RUST_KEYWORDS.contains(&ident.as_str()) => format!("r#{ident}"),
+        _ if ident.starts_with(|c: char| c.is_ascii_digit()) => format!("_{ident}"),
+        _ => ident,
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "unsafe", "use", "where", "while",
+];
+
+/// 保留原来的键名，关键字后加 `_`，不是合法标识符时替换非法字符
+fn python_ident(key: &str) -> String {
+    if PYTHON_KEYWORDS.contains(&key) {
+        return format!("{key}_");
+    }
+    let ident = key
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident
+    }
+}
+
+const PYTHON_KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield", // 会与 `__init__` 的第一个参数重名
+    "self",
+];
+
+/// TypeScript 中不需要加引号的键
+fn is_identifier(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// 带引号和转义的字符串字面量，三种语言通用
+fn quote(key: &str) -> String {
+    Value::from(key).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn sample() -> Value {
+        json!([
+            {"id": 1, "user_info": {"type": "a", "tags": ["x"]}, "note": null},
+            {"id": 2, "user_info": {"type": "b", "tags": []}, "note": "n", "extra-key": 1.5, "mixed": 1},
+            {"id": 3, "user_info": {"type": "c", "tags": []}, "mixed": "s"}
+        ])
+    }
+
+    #[test]
+    fn rust_structs() {
+        let output = generate_types(&sample(), Language::Rust);
+        assert!(output.starts_with("use serde::{Deserialize, Serialize};\n"));
+        assert!(output.contains(
+            "    #[serde(rename = \"extra-key\", skip_serializing_if = \"Option::is_none\")]\n    pub extra_key: Option<f64>,\n    pub id: i64,\n"
+        ));
+        assert!(output.contains("    pub mixed: Option<serde_json::Value>,\n"));
+        assert!(output.contains("    pub user_info: UserInfo,\n"));
+        assert!(output.contains("    #[serde(rename = \"type\")]\n    pub ty: String,\n"));
+    }
+
+    #[test]
+    fn typescript_interfaces() {
+        assert_eq!(
+            generate_types(&sample(), Language::TypeScript),
+            "export interface Root {\n  \"extra-key\"?: number;\n  id: number;\n  mixed?: string | number;\n  note?: string | null;\n  user_info: UserInfo;\n}\n\nexport interface UserInfo {\n  tags: string[];\n  type: string;\n}\n"
+        );
+    }
+
+    #[test]
+    fn python_dataclasses() {
+        let output = generate_types(&sample(), Language::Python);
+        assert!(output.contains("from typing import List, Optional, Union\n"));
+        // 被引用的类在前，可选字段在后
+        let user_info = output.find("class UserInfo:").unwrap();
+        assert!(user_info < output.find("class Root:").unwrap());
+        assert!(output.contains(
+            "    user_info: UserInfo\n    # JSON 中的键：\"extra-key\"\n    extra_key: Optional[float] = None\n"
+        ));
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(pascal_case("userInfo"), "UserInfo");
+        assert_eq!(pascal_case("user_info"), "UserInfo");
+        assert_eq!(rust_ident("type"), "ty");
+        assert_eq!(rust_ident("fooBar"), "foo_bar");
+        assert_eq!(python_ident("class"), "class_");
+        assert!(!is_identifier("extra-key"));
+    }
+}
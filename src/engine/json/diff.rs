@@ -0,0 +1,371 @@
+//! 两个 JSON 文档的结构化对比。
+//!
+//! 对象按键匹配，忽略键的顺序；数组默认按最长公共子序列对齐，也可以忽略元素顺序。
+
+use serde_json::{Value, json};
+
+/// 两个数组待对齐部分长度的乘积超过这个值时不再计算公共子序列，改为按下标逐个对比
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// 左侧文档到右侧文档的差异树
+#[derive(Clone, Debug, PartialEq)]
+pub enum Diff {
+    Same(Value),
+    Added(Value),
+    Removed(Value),
+    Changed {
+        old: Value,
+        new: Value,
+    },
+    /// 对象的每个键及其差异，先按左侧顺序，再是右侧新增的键
+    Object(Vec<(String, Diff)>),
+    /// 数组元素及其差异，下标为新增元素在右侧、其余元素在左侧的位置
+    Array(Vec<(usize, Diff)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ChangeKind {
+    Added(Value),
+    Removed(Value),
+    Changed { old: Value, new: Value },
+}
+
+/// 一处差异，`pointer` 为 JSON Pointer
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub pointer: String,
+    pub kind: ChangeKind,
+}
+
+/// 对比两个文档，`ignore_array_order` 时数组按多重集合对比
+pub fn diff(left: &Value, right: &Value, ignore_array_order: bool) -> Diff {
+    if left == right {
+        return Diff::Same(left.clone());
+    }
+    match (left, right) {
+        (Value::Object(left), Value::Object(right)) => {
+            let mut children = Vec::with_capacity(left.len().max(right.len()));
+            for (key, old) in left {
+                let child = match right.get(key) {
+                    Some(new) => diff(old, new, ignore_array_order),
+                    None => Diff::Removed(old.clone()),
+                };
+                children.push((key.clone(), child));
+            }
+            for (key, new) in right {
+                if !left.contains_key(key) {
+                    children.push((key.clone(), Diff::Added(new.clone())));
+                }
+            }
+            Diff::Object(children)
+        }
+        (Value::Array(left), Value::Array(right)) if ignore_array_order => {
+            Diff::Array(diff_unordered(left, right))
+        }
+        (Value::Array(left), Value::Array(right)) => Diff::Array(diff_ordered(left, right)),
+        _ => Diff::Changed {
+            old: left.clone(),
+            new: right.clone(),
+        },
+    }
+}
+
+/// 相同的元素一一配对，其余的视为删除和新增
+fn diff_unordered(left: &[Value], right: &[Value]) -> Vec<(usize, Diff)> {
+    let mut used = vec![false; right.len()];
+    let mut children = Vec::with_capacity(left.len().max(right.len()));
+    for (i, old) in left.iter().enumerate() {
+        let matched = (0..right.len()).find(|&j| !used[j] && right[j] == *old);
+        let child = match matched {
+            Some(j) => {
+                used[j] = true;
+                Diff::Same(old.clone())
+            }
+            None => Diff::Removed(old.clone()),
+        };
+        children.push((i, child));
+    }
+    for (j, new) in right.iter().enumerate() {
+        if !used[j] {
+            children.push((j, Diff::Added(new.clone())));
+        }
+    }
+    children
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Keep(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// 按公共子序列对齐，相邻的删除和新增配对后递归对比
+fn diff_ordered(left: &[Value], right: &[Value]) -> Vec<(usize, Diff)> {
+    let mut children = Vec::with_capacity(left.len().max(right.len()));
+    let mut deleted = vec![];
+    let mut inserted = vec![];
+    for op in align(left, right).into_iter().map(Some).chain([None]) {
+        match op {
+            Some(Op::Delete(i)) => deleted.push(i),
+            Some(Op::Insert(j)) => inserted.push(j),
+            Some(Op::Keep(i)) => {
+                flush(left, right, &mut deleted, &mut inserted, &mut children);
+                children.push((i, Diff::Same(left[i].clone())));
+            }
+            None => flush(left, right, &mut deleted, &mut inserted, &mut children),
+        }
+    }
+    children
+}
+
+/// 输出一段连续的删除和新增：先是配对的元素，再是多余的删除或新增
+fn flush(
+    left: &[Value],
+    right: &[Value],
+    deleted: &mut Vec<usize>,
+    inserted: &mut Vec<usize>,
+    children: &mut Vec<(usize, Diff)>,
+) {
+    let paired = deleted.len().min(inserted.len());
+    for (&i, &j) in deleted.iter().zip(inserted.iter()) {
+        children.push((i, diff(&left[i], &right[j], false)));
+    }
+    for &i in &deleted[paired..] {
+        children.push((i, Diff::Removed(left[i].clone())));
+    }
+    for &j in &inserted[paired..] {
+        children.push((j, Diff::Added(right[j].clone())));
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+fn align(left: &[Value], right: &[Value]) -> Vec<Op> {
+    // 去掉相同的前缀和后缀，只对中间部分计算公共子序列
+    let prefix = left
+        .iter()
+        .zip(right)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let (n, m) = (left.len() - prefix - suffix, right.len() - prefix - suffix);
+
+    let mut ops: Vec<Op> = (0..prefix).map(Op::Keep).collect();
+    if n.saturating_mul(m) > MAX_LCS_CELLS {
+        ops.extend((prefix..prefix + n).map(Op::Delete));
+        ops.extend((prefix..prefix + m).map(Op::Insert));
+    } else {
+        // lcs[i][j] 为 left[prefix + i..] 与 right[prefix + j..] 中间部分的公共子序列长度
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if left[prefix + i] == right[prefix + j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && left[prefix + i] == right[prefix + j] {
+                ops.push(Op::Keep(prefix + i));
+                i += 1;
+                j += 1;
+            } else if j == m || (i < n && lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push(Op::Delete(prefix + i));
+                i += 1;
+            } else {
+                ops.push(Op::Insert(prefix + j));
+                j += 1;
+            }
+        }
+    }
+    ops.extend((left.len() - suffix..left.len()).map(Op::Keep));
+    ops
+}
+
+impl Diff {
+    pub fn is_same(&self) -> bool {
+        matches!(self, Diff::Same(_))
+    }
+
+    /// 所有差异，路径中的数组下标与 [`Diff::Array`] 相同
+    pub fn changes(&self) -> Vec<Change> {
+        let mut changes = vec![];
+        self.collect_changes(String::new(), &mut changes);
+        changes
+    }
+
+    fn collect_changes(&self, pointer: String, changes: &mut Vec<Change>) {
+        let kind = match self {
+            Diff::Same(_) => return,
+            Diff::Added(value) => ChangeKind::Added(value.clone()),
+            Diff::Removed(value) => ChangeKind::Removed(value.clone()),
+            Diff::Changed { old, new } => ChangeKind::Changed {
+                old: old.clone(),
+                new: new.clone(),
+            },
+            Diff::Object(children) => {
+                for (key, child) in children {
+                    child.collect_changes(format!("{pointer}/{}", escape_token(key)), changes);
+                }
+                return;
+            }
+            Diff::Array(children) => {
+                for (index, child) in children {
+                    child.collect_changes(format!("{pointer}/{index}"), changes);
+                }
+                return;
+            }
+        };
+        changes.push(Change { pointer, kind });
+    }
+
+    fn patch_ops(&self, pointer: String, ops: &mut Vec<Value>) {
+        match self {
+            Diff::Same(_) => {}
+            Diff::Added(value) => ops.push(json!({"op": "add", "path": pointer, "value": value})),
+            Diff::Removed(_) => ops.push(json!({"op": "remove", "path": pointer})),
+            Diff::Changed { new, .. } => {
+                ops.push(json!({"op": "replace", "path": pointer, "value": new}))
+            }
+            Diff::Object(children) => {
+                for (key, child) in children {
+                    child.patch_ops(format!("{pointer}/{}", escape_token(key)), ops);
+                }
+            }
+            Diff::Array(children) => {
+                // 按顺序应用时的当前下标：删除后后面的元素前移
+                let mut index = 0;
+                for (_, child) in children {
+                    child.patch_ops(format!("{pointer}/{index}"), ops);
+                    if !matches!(child, Diff::Removed(_)) {
+                        index += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 转为 JSON Patch（RFC 6902），按顺序应用到左侧文档后得到右侧文档。
+///
+/// 忽略数组顺序时，结果数组中的元素顺序可能与右侧不同。
+pub fn to_patch(diff: &Diff) -> Value {
+    let mut ops = vec![];
+    diff.patch_ops(String::new(), &mut ops);
+    Value::Array(ops)
+}
+
+/// 转义 JSON Pointer 中的一段
+pub fn escape_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按 RFC 6902 依次应用 add/remove/replace
+    fn apply(doc: &Value, patch: &Value) -> Value {
+        let mut doc = doc.clone();
+        for op in patch.as_array().unwrap() {
+            let path = op["path"].as_str().unwrap();
+            let (parent, token) = path.rsplit_once('/').unwrap();
+            let token = token.replace("~1", "/").replace("~0", "~");
+            let value = op.get("value").cloned();
+            match (op["op"].as_str().unwrap(), doc.pointer_mut(parent).unwrap()) {
+                ("add", Value::Array(items)) => {
+                    items.insert(token.parse().unwrap(), value.unwrap());
+                }
+                ("remove", Value::Array(items)) => {
+                    items.remove(token.parse::<usize>().unwrap());
+                }
+                ("add" | "replace", Value::Object(map)) => {
+                    map.insert(token, value.unwrap());
+                }
+                ("remove", Value::Object(map)) => {
+                    map.shift_remove(&token);
+                }
+                ("replace", Value::Array(items)) => {
+                    items[token.parse::<usize>().unwrap()] = value.unwrap();
+                }
+                (op, _) => panic!("unexpected {op} at {path}"),
+            }
+        }
+        doc
+    }
+
+    #[test]
+    fn object_changes_in_key_order() {
+        let left = json!({"b": 1, "a": {"x": 1, "y": 2}, "gone": true});
+        let right = json!({"b": 1, "a": {"x": 1, "y": 3}, "new/key": null});
+        let changes = diff(&left, &right, false).changes();
+        let pointers = changes
+            .iter()
+            .map(|c| c.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(pointers, ["/a/y", "/gone", "/new~1key"]);
+        assert_eq!(
+            changes[0].kind,
+            ChangeKind::Changed {
+                old: json!(2),
+                new: json!(3)
+            }
+        );
+    }
+
+    #[test]
+    fn patch_indices_follow_earlier_operations() {
+        let left = json!([1, 2, 3, 4, 5]);
+        let right = json!([1, 3, 9, 5, 6]);
+        let patch = to_patch(&diff(&left, &right, false));
+        assert_eq!(
+            patch,
+            json!([
+                {"op": "remove", "path": "/1"},
+                {"op": "replace", "path": "/2", "value": 9},
+                {"op": "add", "path": "/4", "value": 6}
+            ])
+        );
+        assert_eq!(apply(&left, &patch), right);
+    }
+
+    #[test]
+    fn patch_round_trips_nested_documents() {
+        let left = json!({"items": [{"id": 1}, {"id": 2, "tags": ["a"]}, {"id": 3}], "v": 1});
+        let right = json!({"items": [{"id": 0}, {"id": 2, "tags": ["a", "b"]}], "v": "1"});
+        let patch = to_patch(&diff(&left, &right, false));
+        assert_eq!(apply(&left, &patch), right);
+        assert_eq!(to_patch(&diff(&left, &left, false)), json!([]));
+        assert!(diff(&left, &left, false).is_same());
+    }
+
+    #[test]
+    fn ignore_array_order() {
+        let left = json!([1, 2, 2, 3]);
+        let right = json!([3, 2, 1, 4]);
+        let diff = diff(&left, &right, true);
+        let changes = diff.changes();
+        assert_eq!(
+            changes,
+            [
+                Change {
+                    pointer: "/2".to_owned(),
+                    kind: ChangeKind::Removed(json!(2))
+                },
+                Change {
+                    pointer: "/3".to_owned(),
+                    kind: ChangeKind::Added(json!(4))
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! 按 JSON Pointer 修改文档，用于树形视图中的编辑。
+
+use serde_json::Value;
+
+/// 一次编辑，路径均为 JSON Pointer
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    /// 替换值
+    Replace {
+        pointer: String,
+        value: Value,
+    },
+    /// 重命名对象中的键
+    Rename {
+        pointer: String,
+        key: String,
+    },
+    Remove {
+        pointer: String,
+    },
+    /// 在对象中添加键；`parent` 为数组时 `key` 为插入位置的下标，`-` 表示追加到末尾
+    Insert {
+        parent: String,
+        key: String,
+        value: Value,
+    },
+    /// 把数组元素移动到同一数组的下标 `to`
+    Move {
+        pointer: String,
+        to: usize,
+    },
+}
+
+/// 执行编辑，路径不存在或不适用时返回错误，文档保持不变
+pub fn apply_edit(root: &mut Value, edit: Edit) -> Result<(), String> {
+    match edit {
+        Edit::Replace { pointer, value } => {
+            *root
+                .pointer_mut(&pointer)
+                .ok_or_else(|| not_found(&pointer))? = value;
+            Ok(())
+        }
+        Edit::Rename { pointer, key } => {
+            let (parent, old) = split(&pointer)?;
+            let Some(Value::Object(map)) = root.pointer_mut(parent) else {
+                return Err("只能重命名对象中的键".to_owned());
+            };
+            if old == key {
+                return Ok(());
+            }
+            if map.contains_key(&key) {
+                return Err(format!("键 \"{key}\" 已存在"));
+            }
+            // 保持键的位置不变
+            let index = map
+                .keys()
+                .position(|k| *k == old)
+                .ok_or_else(|| not_found(&pointer))?;
+            let value = map.shift_remove(&old).unwrap_or_default();
+            map.shift_insert(index, key, value);
+            Ok(())
+        }
+        Edit::Remove { pointer } => {
+            let (parent, token) = split(&pointer)?;
+            let removed = match root.pointer_mut(parent) {
+                Some(Value::Object(map)) => map.shift_remove(&token).is_some(),
+                Some(Value::Array(items)) => match index(&token, items.len()) {
+                    Ok(index) => {
+                        items.remove(index);
+                        true
+                    }
+                    Err(_) => false,
+                },
+                _ => false,
+            };
+            if !removed {
+                return Err(not_found(&pointer));
+            }
+            Ok(())
+        }
+        Edit::Insert { parent, key, value } => match root.pointer_mut(&parent) {
+            Some(Value::Object(map)) => {
+                if map.contains_key(&key) {
+                    return Err(format!("键 \"{key}\" 已存在"));
+                }
+                map.insert(key, value);
+                Ok(())
+            }
+            Some(Value::Array(items)) => {
+                let index = if key == "-" {
+                    items.len()
+                } else {
+                    index(&key, items.len() + 1)?
+                };
+                items.insert(index, value);
+                Ok(())
+            }
+            Some(_) => Err("只能在对象或数组中插入".to_owned()),
+            None => Err(not_found(&parent)),
+        },
+        Edit::Move { pointer, to } => {
+            let (parent, token) = split(&pointer)?;
+            let Some(Value::Array(items)) = root.pointer_mut(parent) else {
+                return Err("只能移动数组中的元素".to_owned());
+            };
+            let from = index(&token, items.len())?;
+            if to >= items.len() {
+                return Err(format!("下标 {to} 超出数组范围"));
+            }
+            let item = items.remove(from);
+            items.insert(to, item);
+            Ok(())
+        }
+    }
+}
+
+/// 拆分为上一级的路径和最后一段（已反转义）
+fn split(pointer: &str) -> Result<(&str, String), String> {
+    let (parent, token) = pointer
+        .rsplit_once('/')
+        .ok_or_else(|| "不能对根节点执行此操作".to_owned())?;
+    Ok((parent, token.replace("~1", "/").replace("~0", "~")))
+}
+
+fn index(token: &str, len: usize) -> Result<usize, String> {
+    match token.parse::<usize>() {
+        Ok(index) if index < len => Ok(index),
+        _ => Err(format!("下标 {token} 超出数组范围")),
+    }
+}
+
+fn not_found(pointer: &str) -> String {
+    format!("路径 {pointer} 不存在")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn edited(edit: Edit) -> Result<Value, String> {
+        let mut root = json!({"b": 1, "a/x": [1, 2, 3], "c": {"d": null}});
+        apply_edit(&mut root, edit).map(|()| root)
+    }
+
+    #[test]
+    fn rename_keeps_position() {
+        let root = edited(Edit::Rename {
+            pointer: "/a~1x".to_owned(),
+            key: "z".to_owned(),
+        })
+        .unwrap();
+        assert_eq!(root.to_string(), r#"{"b":1,"z":[1,2,3],"c":{"d":null}}"#);
+        let error = edited(Edit::Rename {
+            pointer: "/b".to_owned(),
+            key: "c".to_owned(),
+        });
+        assert_eq!(error, Err("键 \"c\" 已存在".to_owned()));
+    }
+
+    #[test]
+    fn replace_and_remove() {
+        let root = edited(Edit::Replace {
+            pointer: "/c/d".to_owned(),
+            value: json!("x"),
+        })
+        .unwrap();
+        assert_eq!(root["c"]["d"], "x");
+        let root = edited(Edit::Remove {
+            pointer: "/b".to_owned(),
+        })
+        .unwrap();
+        assert_eq!(root.to_string(), r#"{"a/x":[1,2,3],"c":{"d":null}}"#);
+        let root = edited(Edit::Remove {
+            pointer: "/a~1x/1".to_owned(),
+        })
+        .unwrap();
+        assert_eq!(root["a/x"], json!([1, 3]));
+        assert!(
+            edited(Edit::Remove {
+                pointer: String::new()
+            })
+            .is_err()
+        );
+        assert!(
+            edited(Edit::Remove {
+                pointer: "/a~1x/3".to_owned()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn insert_and_move() {
+        let insert = |parent: &str, key: &str| {
+            edited(Edit::Insert {
+                parent: parent.to_owned(),
+                key: key.to_owned(),
+                value: json!(0),
+            })
+        };
+        assert_eq!(insert("/a~1x", "0").unwrap()["a/x"], json!([0, 1, 2, 3]));
+        assert_eq!(insert("/a~1x", "-").unwrap()["a/x"], json!([1, 2, 3, 0]));
+        assert_eq!(insert("/c", "e").unwrap()["c"], json!({"d": null, "e": 0}));
+        assert!(insert("/a~1x", "5").is_err());
+        assert!(insert("/b", "x").is_err());
+        let root = edited(Edit::Move {
+            pointer: "/a~1x/0".to_owned(),
+            to: 2,
+        })
+        .unwrap();
+        assert_eq!(root["a/x"], json!([2, 3, 1]));
+    }
+}
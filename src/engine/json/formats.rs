@@ -0,0 +1,712 @@
+//! JSON 与 YAML、TOML、CSV、MessagePack、CBOR 互转。
+//!
+//! 所有格式都先转为 [`Value`]，再写为目标格式。无法原样保留的内容（如 TOML 中的 null、
+//! CSV 中的嵌套值、YAML 中非字符串的键）会记录在 [`Lossy`] 中。
+
+use std::{fmt, str::FromStr};
+
+use base64::{
+    Engine,
+    engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use super::SyntaxError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+    /// 对象数组，每个对象一行
+    Csv,
+    MessagePack,
+    Cbor,
+}
+
+/// 二进制格式在文本框中的表示
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BinaryEncoding {
+    Hex,
+    Base64,
+}
+
+pub const ALL_FORMATS: [DataFormat; 6] = [
+    DataFormat::Json,
+    DataFormat::Yaml,
+    DataFormat::Toml,
+    DataFormat::Csv,
+    DataFormat::MessagePack,
+    DataFormat::Cbor,
+];
+
+impl DataFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataFormat::Json => "JSON",
+            DataFormat::Yaml => "YAML",
+            DataFormat::Toml => "TOML",
+            DataFormat::Csv => "CSV",
+            DataFormat::MessagePack => "MessagePack",
+            DataFormat::Cbor => "CBOR",
+        }
+    }
+
+    pub fn is_binary(&self) -> bool {
+        matches!(self, DataFormat::MessagePack | DataFormat::Cbor)
+    }
+}
+
+impl fmt::Display for DataFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+impl FromStr for DataFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(DataFormat::Json),
+            "yaml" | "yml" => Ok(DataFormat::Yaml),
+            "toml" => Ok(DataFormat::Toml),
+            "csv" => Ok(DataFormat::Csv),
+            "msgpack" | "messagepack" => Ok(DataFormat::MessagePack),
+            "cbor" => Ok(DataFormat::Cbor),
+            _ => Err(format!("未知的格式：{s}")),
+        }
+    }
+}
+
+/// 有损转换的说明，相同的说明只记录一次
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lossy(Vec<&'static str>);
+
+impl Lossy {
+    fn add(&mut self, note: &'static str) {
+        if !self.0.contains(&note) {
+            self.0.push(note);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Display for Lossy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "⚠ 有损转换：{}", self.0.join("；"))
+    }
+}
+
+/// 按 `format` 解析输入，JSON 按标准语法解析
+pub fn decode(
+    input: &str,
+    format: DataFormat,
+    encoding: BinaryEncoding,
+    lossy: &mut Lossy,
+) -> Result<Value, SyntaxError> {
+    match format {
+        DataFormat::Json => {
+            serde_json::from_str(input).map_err(|e| SyntaxError::from_serde(input, &e))
+        }
+        DataFormat::Yaml => decode_yaml(input, lossy),
+        DataFormat::Toml => {
+            let table = toml::from_str::<toml::Table>(input).map_err(|e| {
+                let offset = e.span().map_or(0, |span| span.start);
+                SyntaxError::at(input, offset, e.message().trim())
+            })?;
+            Ok(from_toml(toml::Value::Table(table), lossy))
+        }
+        DataFormat::Csv => decode_csv(input, lossy),
+        DataFormat::MessagePack => {
+            let bytes = decode_binary(input, encoding)?;
+            let value = rmp_serde::from_slice::<ciborium::Value>(&bytes)
+                .map_err(|e| SyntaxError::at(input, 0, format!("MessagePack 解析失败：{e}")))?;
+            Ok(from_cbor(value, lossy))
+        }
+        DataFormat::Cbor => {
+            let bytes = decode_binary(input, encoding)?;
+            let value = ciborium::from_reader::<ciborium::Value, _>(bytes.as_slice())
+                .map_err(|e| SyntaxError::at(input, 0, format!("CBOR 解析失败：{e}")))?;
+            Ok(from_cbor(value, lossy))
+        }
+    }
+}
+
+/// 写为 `format`，`pretty` 只对 JSON 有效
+pub fn encode(
+    value: &Value,
+    format: DataFormat,
+    encoding: BinaryEncoding,
+    pretty: bool,
+    lossy: &mut Lossy,
+) -> Result<String, String> {
+    match format {
+        DataFormat::Json if pretty => {
+            serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+        }
+        DataFormat::Json => serde_json::to_string(value).map_err(|e| e.to_string()),
+        DataFormat::Yaml => serde_yaml_ng::to_string(value).map_err(|e| e.to_string()),
+        DataFormat::Toml => {
+            let Some(toml::Value::Table(table)) = to_toml(value, lossy) else {
+                return Err("TOML 的根节点必须是对象".to_owned());
+            };
+            toml::to_string_pretty(&table).map_err(|e| e.to_string())
+        }
+        DataFormat::Csv => encode_csv(value, lossy),
+        DataFormat::MessagePack => {
+            let bytes = rmp_serde::to_vec(value).map_err(|e| e.to_string())?;
+            Ok(encode_binary(&bytes, encoding))
+        }
+        DataFormat::Cbor => {
+            let mut bytes = vec![];
+            ciborium::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+            Ok(encode_binary(&bytes, encoding))
+        }
+    }
+}
+
+fn decode_yaml(input: &str, lossy: &mut Lossy) -> Result<Value, SyntaxError> {
+    let error = |e: serde_yaml_ng::Error| {
+        let (message, described) = strip_yaml_locations(e.to_string());
+        // 位置以错误本身携带的为准，没有时才使用消息中的
+        match e.location().map(|l| (l.line(), l.column())).or(described) {
+            Some((line, column)) => SyntaxError {
+                message,
+                line,
+                column,
+            },
+            None => SyntaxError::at(input, 0, message),
+        }
+    };
+    let mut documents = serde_yaml_ng::Deserializer::from_str(input)
+        .map(|document| serde_yaml_ng::Value::deserialize(document).map_err(error))
+        .collect::<Result<Vec<_>, _>>()?;
+    if documents.len() > 1 {
+        lossy.add("多个 YAML 文档已合并为数组");
+        let documents = documents.into_iter().map(|doc| from_yaml(doc, lossy));
+        return Ok(Value::Array(documents.collect()));
+    }
+    Ok(documents
+        .pop()
+        .map_or(Value::Null, |document| from_yaml(document, lossy)))
+}
+
+/// 去掉消息中的“ at line N column M”，返回第一个位置。
+///
+/// 消息中可能有多个位置，第一个是出错的位置，其余是所在结构的起点。
+fn strip_yaml_locations(mut message: String) -> (String, Option<(usize, usize)>) {
+    let mut location = None;
+    while let Some(start) = message.find(" at line ") {
+        let rest = &message[start + " at line ".len()..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == ' ' || "column".contains(c)))
+            .unwrap_or(rest.len());
+        let mut numbers = rest[..end]
+            .split(" column ")
+            .map(|n| n.trim().parse::<usize>().unwrap_or(1));
+        if location.is_none() {
+            location = numbers.next().zip(numbers.next());
+        }
+        message.replace_range(start..start + " at line ".len() + end, "");
+    }
+    (message, location)
+}
+
+fn from_yaml(value: serde_yaml_ng::Value, lossy: &mut Lossy) -> Value {
+    match value {
+        serde_yaml_ng::Value::Null => Value::Null,
+        serde_yaml_ng::Value::Bool(b) => Value::Bool(b),
+        serde_yaml_ng::Value::Number(n) => {
+            if let Some(n) = n.as_i64() {
+                Value::from(n)
+            } else if let Some(n) = n.as_u64() {
+                Value::from(n)
+            } else {
+                float(n.as_f64().unwrap_or(f64::NAN), lossy)
+            }
+        }
+        serde_yaml_ng::Value::String(s) => Value::String(s),
+        serde_yaml_ng::Value::Sequence(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| from_yaml(item, lossy))
+                .collect(),
+        ),
+        serde_yaml_ng::Value::Mapping(mapping) => {
+            let mut map = Map::new();
+            for (key, value) in mapping {
+                let key = match key {
+                    serde_yaml_ng::Value::String(key) => key,
+                    key => {
+                        lossy.add("非字符串的键已转为字符串");
+                        match from_yaml(key, lossy) {
+                            Value::String(key) => key,
+                            key => key.to_string(),
+                        }
+                    }
+                };
+                map.insert(key, from_yaml(value, lossy));
+            }
+            Value::Object(map)
+        }
+        serde_yaml_ng::Value::Tagged(tagged) => {
+            lossy.add("YAML 标签已忽略");
+            from_yaml(tagged.value, lossy)
+        }
+    }
+}
+
+fn from_toml(value: toml::Value, lossy: &mut Lossy) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(n) => Value::from(n),
+        toml::Value::Float(n) => float(n, lossy),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => {
+            lossy.add("日期时间已转为字符串");
+            Value::String(datetime.to_string())
+        }
+        toml::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| from_toml(item, lossy))
+                .collect(),
+        ),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, from_toml(value, lossy)))
+                .collect(),
+        ),
+    }
+}
+
+/// TOML 没有 null，对象和数组中的 null 会被省略
+fn to_toml(value: &Value, lossy: &mut Lossy) -> Option<toml::Value> {
+    Some(match value {
+        Value::Null => {
+            lossy.add("TOML 不支持 null，已省略");
+            return None;
+        }
+        Value::Bool(b) => toml::Value::Boolean(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(n) => toml::Value::Integer(n),
+            None => {
+                if n.is_u64() {
+                    lossy.add("超出 TOML 整数范围的数已转为浮点数");
+                }
+                toml::Value::Float(n.as_f64().unwrap_or_default())
+            }
+        },
+        Value::String(s) => toml::Value::String(s.clone()),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .filter_map(|item| to_toml(item, lossy))
+                .collect(),
+        ),
+        Value::Object(map) => toml::Value::Table(
+            map.iter()
+                .filter_map(|(key, value)| Some((key.clone(), to_toml(value, lossy)?)))
+                .collect(),
+        ),
+    })
+}
+
+fn from_cbor(value: ciborium::Value, lossy: &mut Lossy) -> Value {
+    match value {
+        ciborium::Value::Integer(n) => {
+            let n = i128::from(n);
+            i64::try_from(n)
+                .map(Value::from)
+                .or_else(|_| u64::try_from(n).map(Value::from))
+                .unwrap_or_else(|_| {
+                    lossy.add("超出 64 位范围的整数已转为浮点数");
+                    float(n as f64, lossy)
+                })
+        }
+        ciborium::Value::Bytes(bytes) => {
+            lossy.add("二进制数据已转为 base64 字符串");
+            Value::String(STANDARD.encode(bytes))
+        }
+        ciborium::Value::Float(n) => float(n, lossy),
+        ciborium::Value::Text(s) => Value::String(s),
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Tag(_, value) => {
+            lossy.add("CBOR 标签已忽略");
+            from_cbor(*value, lossy)
+        }
+        ciborium::Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .map(|item| from_cbor(item, lossy))
+                .collect(),
+        ),
+        ciborium::Value::Map(entries) => {
+            let mut map = Map::new();
+            for (key, value) in entries {
+                let key = match key {
+                    ciborium::Value::Text(key) => key,
+                    key => {
+                        lossy.add("非字符串的键已转为字符串");
+                        match from_cbor(key, lossy) {
+                            Value::String(key) => key,
+                            key => key.to_string(),
+                        }
+                    }
+                };
+                map.insert(key, from_cbor(value, lossy));
+            }
+            Value::Object(map)
+        }
+        _ => {
+            lossy.add("无法识别的值已转为 null");
+            Value::Null
+        }
+    }
+}
+
+/// JSON 无法表示 NaN 和无穷大，转为 null
+fn float(n: f64, lossy: &mut Lossy) -> Value {
+    Number::from_f64(n).map_or_else(
+        || {
+            lossy.add("NaN 和无穷大已转为 null");
+            Value::Null
+        },
+        Value::Number,
+    )
+}
+
+/// 第一行为表头，每行转为一个对象，单元格按内容推断为数值或布尔值
+fn decode_csv(input: &str, lossy: &mut Lossy) -> Result<Value, SyntaxError> {
+    let mut reader = csv::Reader::from_reader(input.as_bytes());
+    let error = |e: csv::Error| {
+        let offset = e.position().map_or(0, |p| p.byte() as usize);
+        let message = match e.kind() {
+            csv::ErrorKind::UnequalLengths {
+                expected_len, len, ..
+            } => format!("应有 {expected_len} 列，实际为 {len} 列"),
+            _ => e.to_string(),
+        };
+        SyntaxError::at(input, offset.min(input.len()), message)
+    };
+    let headers = reader.headers().map_err(error)?.clone();
+    let mut rows = vec![];
+    for record in reader.records() {
+        let record = record.map_err(error)?;
+        let row = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(key, cell)| (key.to_owned(), cell_value(cell, lossy)))
+            .collect();
+        rows.push(Value::Object(row));
+    }
+    Ok(Value::Array(rows))
+}
+
+fn cell_value(cell: &str, lossy: &mut Lossy) -> Value {
+    match cell {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => match serde_json::from_str::<Number>(cell) {
+            Ok(n) => {
+                // 如 `1e3`、` 1` 或超出 f64 精度的数，写回 CSV 时与原文不同
+                if n.to_string() != cell {
+                    lossy.add("部分数值的写法或精度已改变");
+                }
+                Value::Number(n)
+            }
+            Err(_) => Value::String(cell.to_owned()),
+        },
+    }
+}
+
+/// 对象数组转为 CSV，列为所有对象的键，按出现顺序排列
+fn encode_csv(value: &Value, lossy: &mut Lossy) -> Result<String, String> {
+    let rows = match value {
+        Value::Array(rows) => rows.as_slice(),
+        Value::Object(_) => std::slice::from_ref(value),
+        _ => return Err("CSV 需要对象数组".to_owned()),
+    };
+    let mut columns: Vec<&str> = vec![];
+    for row in rows {
+        let Value::Object(row) = row else {
+            return Err("CSV 需要对象数组，数组中有不是对象的元素".to_owned());
+        };
+        for key in row.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let mut writer = csv::Writer::from_writer(vec![]);
+    writer.write_record(&columns).map_err(|e| e.to_string())?;
+    for row in rows {
+        let Value::Object(row) = row else {
+            continue;
+        };
+        let cells = columns.iter().map(|column| match row.get(*column) {
+            None => {
+                lossy.add("缺少的字段已留空");
+                String::new()
+            }
+            Some(Value::Null) => {
+                lossy.add("null 已转为空单元格");
+                String::new()
+            }
+            Some(Value::String(s)) => s.clone(),
+            Some(value @ (Value::Array(_) | Value::Object(_))) => {
+                lossy.add("嵌套的值已转为 JSON 字符串");
+                value.to_string()
+            }
+            Some(value) => value.to_string(),
+        });
+        writer
+            .write_record(cells.collect::<Vec<_>>())
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+/// 忽略空白、`0x` 前缀和字节间的分隔符
+fn decode_binary(input: &str, encoding: BinaryEncoding) -> Result<Vec<u8>, SyntaxError> {
+    match encoding {
+        BinaryEncoding::Hex => {
+            let trimmed = input.trim_start();
+            let body = trimmed
+                .strip_prefix("0x")
+                .or_else(|| trimmed.strip_prefix("0X"))
+                .unwrap_or(trimmed);
+            let offset = input.len() - body.len();
+            let mut digits = vec![];
+            for (index, c) in body.char_indices() {
+                match c.to_digit(16) {
+                    Some(digit) => digits.push(digit as u8),
+                    None if c.is_whitespace() || matches!(c, ':' | '-' | ',') => {}
+                    None => {
+                        return Err(SyntaxError::at(
+                            input,
+                            offset + index,
+                            format!("`{c}` 不是十六进制数字"),
+                        ));
+                    }
+                }
+            }
+            if digits.len() % 2 != 0 {
+                return Err(SyntaxError::at(
+                    input,
+                    input.len(),
+                    "十六进制数字的个数应为偶数",
+                ));
+            }
+            Ok(digits
+                .chunks(2)
+                .map(|pair| pair[0] << 4 | pair[1])
+                .collect())
+        }
+        BinaryEncoding::Base64 => {
+            let text: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+            STANDARD
+                .decode(&text)
+                .or_else(|_| URL_SAFE_NO_PAD.decode(text.trim_end_matches('=')))
+                .map_err(|e| SyntaxError::at(input, 0, format!("base64 解码失败：{e}")))
+        }
+    }
+}
+
+fn encode_binary(bytes: &[u8], encoding: BinaryEncoding) -> String {
+    match encoding {
+        BinaryEncoding::Hex => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+        BinaryEncoding::Base64 => STANDARD.encode(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn round_trip(value: &Value, format: DataFormat, encoding: BinaryEncoding) -> (Value, Lossy) {
+        let mut lossy = Lossy::default();
+        let text = encode(value, format, encoding, false, &mut lossy).unwrap();
+        let decoded = decode(&text, format, encoding, &mut lossy).unwrap();
+        (decoded, lossy)
+    }
+
+    #[test]
+    fn lossless_round_trips() {
+        let value = json!({"name": "中文", "n": -3, "pi": 1.5, "ok": true, "list": [1, "a"], "nested": {"x": 2}});
+        for format in [DataFormat::Json, DataFormat::Yaml, DataFormat::Toml] {
+            assert_eq!(
+                round_trip(&value, format, BinaryEncoding::Hex),
+                (value.clone(), Lossy::default()),
+                "{format}"
+            );
+        }
+        let value = json!({"nil": null, "big": u64::MAX, "list": [1, {"a": "b"}]});
+        for format in [DataFormat::MessagePack, DataFormat::Cbor] {
+            for encoding in [BinaryEncoding::Hex, BinaryEncoding::Base64] {
+                assert_eq!(
+                    round_trip(&value, format, encoding),
+                    (value.clone(), Lossy::default()),
+                    "{format}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn toml_drops_null() {
+        let (decoded, lossy) = round_trip(
+            &json!({"a": null, "b": 1}),
+            DataFormat::Toml,
+            BinaryEncoding::Hex,
+        );
+        assert_eq!(decoded, json!({"b": 1}));
+        assert_eq!(lossy.to_string(), "⚠ 有损转换：TOML 不支持 null，已省略");
+        let mut lossy = Lossy::default();
+        assert!(
+            encode(
+                &json!([1]),
+                DataFormat::Toml,
+                BinaryEncoding::Hex,
+                false,
+                &mut lossy
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn yaml_documents_and_keys() {
+        let mut lossy = Lossy::default();
+        let value = decode(
+            "a: 1\n---\n2: [x, 1.0]\n",
+            DataFormat::Yaml,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap();
+        assert_eq!(value, json!([{"a": 1}, {"2": ["x", 1.0]}]));
+        assert_eq!(
+            lossy.to_string(),
+            "⚠ 有损转换：多个 YAML 文档已合并为数组；非字符串的键已转为字符串"
+        );
+        let error = decode(
+            "a: [1\nb: 2",
+            DataFormat::Yaml,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap_err();
+        assert!(error.line >= 1);
+        assert!(!error.message.contains(" at line "), "{}", error.message);
+        // 位置来自错误本身，列号按字符计算
+        let error = decode(
+            "名字: 值\n列表: 值: x\n",
+            DataFormat::Yaml,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        let message = "expected ',' at line 2 column 5, while parsing at line 1 column 4";
+        assert_eq!(
+            strip_yaml_locations(message.to_owned()),
+            ("expected ',', while parsing".to_owned(), Some((2, 5)))
+        );
+    }
+
+    #[test]
+    fn csv_rows() {
+        let mut lossy = Lossy::default();
+        let value = decode(
+            "id,name,ok\n1,a,true\n2,\"b,c\",x\n",
+            DataFormat::Csv,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!([{"id": 1, "name": "a", "ok": true}, {"id": 2, "name": "b,c", "ok": "x"}])
+        );
+        assert!(lossy.is_empty());
+        let mut lossy = Lossy::default();
+        let value = decode(
+            "n\n1e3\n007\n",
+            DataFormat::Csv,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap();
+        assert_eq!(value, json!([{"n": 1000.0}, {"n": "007"}]));
+        assert_eq!(lossy.to_string(), "⚠ 有损转换：部分数值的写法或精度已改变");
+        let mut lossy = Lossy::default();
+        let text = encode(
+            &json!([{"a": 1, "b": [2]}, {"c": null}]),
+            DataFormat::Csv,
+            BinaryEncoding::Hex,
+            false,
+            &mut lossy,
+        )
+        .unwrap();
+        assert_eq!(text, "a,b,c\n1,[2],\n,,\n");
+        assert!(!lossy.is_empty());
+        let error =
+            decode("a,b\n1\n", DataFormat::Csv, BinaryEncoding::Hex, &mut lossy).unwrap_err();
+        assert_eq!(
+            (error.line, error.message.as_str()),
+            (2, "应有 2 列，实际为 1 列")
+        );
+    }
+
+    #[test]
+    fn binary_input_is_lenient() {
+        let mut lossy = Lossy::default();
+        // MessagePack 的 [1, 2]
+        let value = decode(
+            "0x92 01:02",
+            DataFormat::MessagePack,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap();
+        assert_eq!(value, json!([1, 2]));
+        assert!(
+            decode(
+                "9",
+                DataFormat::MessagePack,
+                BinaryEncoding::Hex,
+                &mut lossy
+            )
+            .is_err()
+        );
+        assert!(decode("zz", DataFormat::Cbor, BinaryEncoding::Hex, &mut lossy).is_err());
+        // 报告出错字符实际所在的位置，而不是它第一次出现的位置
+        let error = decode(
+            "0x92\n01 x2",
+            DataFormat::MessagePack,
+            BinaryEncoding::Hex,
+            &mut lossy,
+        )
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn format_names() {
+        assert_eq!("yml".parse(), Ok(DataFormat::Yaml));
+        assert_eq!("MsgPack".parse(), Ok(DataFormat::MessagePack));
+        assert!("xml".parse::<DataFormat>().is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! 宽松的 JSON 解析，兼容 JSON5 与 JSONC。
+//!
+//! 在 JSON 的基础上支持 `//` 和 `/* */` 注释、末尾逗号、不加引号的键、单引号字符串、
+//! 字符串中的续行、十六进制数、`+` 号以及省略整数或小数部分的数值。
+//! `Infinity` 和 `NaN` 无法用 JSON 表示，解析时报错。
+
+use serde_json::{Map, Number, Value};
+
+use super::SyntaxError;
+
+/// 解析 JSON5 或 JSONC 文档
+pub fn parse_json5(input: &str) -> Result<Value, SyntaxError> {
+    let mut parser = Parser { input, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace()?;
+    if parser.pos < input.len() {
+        return Err(parser.error("文档结束后还有多余的内容"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: impl Into<String>) -> SyntaxError {
+        SyntaxError::at(self.input, self.pos, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    /// 跳过空白和注释，注释没有结束时报错
+    fn skip_whitespace(&mut self) -> Result<(), SyntaxError> {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let end = comment
+                    .find("*/")
+                    .ok_or_else(|| self.error("注释没有结束"))?;
+                self.pos += end + 4;
+            } else if let Some(c) = self.peek()
+                && (c.is_whitespace() || c == '\u{feff}')
+            {
+                self.pos += c.len_utf8();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, SyntaxError> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            None => Err(self.error("缺少值")),
+            Some('{') => self.object(),
+            Some('[') => self.array(),
+            Some(quote @ ('\'' | '"')) => self.string(quote).map(Value::String),
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.number(),
+            Some(c) if is_identifier_start(c) => {
+                let start = self.pos;
+                match self.identifier() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    name @ ("Infinity" | "NaN") => {
+                        self.pos = start;
+                        Err(self.error(format!("JSON 不支持 {name}")))
+                    }
+                    name => {
+                        self.pos = start;
+                        Err(self.error(format!("无法识别的值 `{name}`，字符串需要加引号")))
+                    }
+                }
+            }
+            Some(c) => Err(self.error(format!("无法识别的字符 `{c}`"))),
+        }
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let input = self.input;
+        let start = self.pos;
+        while self.peek().is_some_and(is_identifier_part) {
+            self.bump();
+        }
+        &input[start..self.pos]
+    }
+
+    fn object(&mut self) -> Result<Value, SyntaxError> {
+        self.pos += 1;
+        let mut map = Map::new();
+        loop {
+            self.skip_whitespace()?;
+            let key = match self.peek() {
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(map));
+                }
+                Some(quote @ ('\'' | '"')) => self.string(quote)?,
+                Some(c) if is_identifier_start(c) => self.identifier().to_owned(),
+                Some(c) => return Err(self.error(format!("应为键或 `}}`，实际为 `{c}`"))),
+                None => return Err(self.error("缺少 `}`")),
+            };
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(':') => self.pos += 1,
+                Some(c) => return Err(self.error(format!("键后应为 `:`，实际为 `{c}`"))),
+                None => return Err(self.error("键后缺少 `:`")),
+            }
+            map.insert(key, self.value()?);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                Some(c) => return Err(self.error(format!("应为 `,` 或 `}}`，实际为 `{c}`"))),
+                None => return Err(self.error("缺少 `}`")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, SyntaxError> {
+        self.pos += 1;
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                Some(c) => return Err(self.error(format!("应为 `,` 或 `]`，实际为 `{c}`"))),
+                None => return Err(self.error("缺少 `]`")),
+            }
+        }
+    }
+
+    fn string(&mut self, quote: char) -> Result<String, SyntaxError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut text = String::new();
+        loop {
+            match self.bump() {
+                None => {
+                    self.pos = start;
+                    return Err(self.error("字符串没有结束"));
+                }
+                Some(c) if c == quote => return Ok(text),
+                Some('\n' | '\r') => {
+                    self.pos -= 1;
+                    return Err(self.error("字符串中不能直接换行，需要写作 \\n"));
+                }
+                Some('\\') => self.escape(&mut text)?,
+                Some(c) => text.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self, text: &mut String) -> Result<(), SyntaxError> {
+        let start = self.pos - 1;
+        let Some(c) = self.bump() else {
+            return Err(self.error("字符串没有结束"));
+        };
+        match c {
+            // 续行
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            '\r' => {
+                if self.peek() == Some('\n') {
+                    self.pos += 1;
+                }
+            }
+            'b' => text.push('\u{8}'),
+            'f' => text.push('\u{c}'),
+            'n' => text.push('\n'),
+            'r' => text.push('\r'),
+            't' => text.push('\t'),
+            'v' => text.push('\u{b}'),
+            '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => text.push('\0'),
+            '1'..='9' | '0' => {
+                self.pos = start;
+                return Err(self.error("不支持八进制转义"));
+            }
+            'x' => {
+                let code = self.hex(2, start)?;
+                text.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            'u' => {
+                let code = self.hex(4, start)?;
+                let c = if (0xd800..0xdc00).contains(&code) && self.rest().starts_with("\\u") {
+                    // 代理对
+                    self.pos += 2;
+                    let low = self.hex(4, start)?;
+                    char::from_u32(0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00)))
+                } else {
+                    char::from_u32(code)
+                };
+                text.push(c.unwrap_or('\u{fffd}'));
+            }
+            c => text.push(c),
+        }
+        Ok(())
+    }
+
+    fn hex(&mut self, len: usize, start: usize) -> Result<u32, SyntaxError> {
+        let digits = self.rest().get(..len).unwrap_or_default();
+        if digits.len() != len || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            self.pos = start;
+            return Err(self.error(format!("转义后应为 {len} 位十六进制数")));
+        }
+        self.pos += len;
+        Ok(u32::from_str_radix(digits, 16).unwrap_or_default())
+    }
+
+    fn number(&mut self) -> Result<Value, SyntaxError> {
+        let start = self.pos;
+        let negative = match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                true
+            }
+            Some('+') => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+        if let Some(name @ ("Infinity" | "NaN")) = ["Infinity", "NaN"]
+            .into_iter()
+            .find(|name| self.rest().starts_with(name))
+        {
+            self.pos = start;
+            return Err(self.error(format!("JSON 不支持 {name}")));
+        }
+
+        let digits_start = self.pos;
+        let rest = self.rest();
+        let value = if rest.starts_with("0x") || rest.starts_with("0X") {
+            self.pos += 2;
+            let hex_start = self.pos;
+            while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                self.pos += 1;
+            }
+            let n = u64::from_str_radix(&self.input[hex_start..self.pos], 16).ok();
+            n.and_then(|n| {
+                if negative {
+                    0i64.checked_sub_unsigned(n).map(Number::from)
+                } else {
+                    Some(Number::from(n))
+                }
+            })
+        } else {
+            while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+                self.pos += 1;
+            }
+            if matches!(self.peek(), Some('e' | 'E')) {
+                self.pos += 1;
+                if matches!(self.peek(), Some('+' | '-')) {
+                    self.pos += 1;
+                }
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+            let literal = &self.input[digits_start..self.pos];
+            let sign = if negative { "-" } else { "" };
+            let int = literal.strip_prefix('0').unwrap_or(literal);
+            if literal.len() > 1
+                && literal.starts_with('0')
+                && int.starts_with(|c: char| c.is_ascii_digit())
+            {
+                // 与 JSON 相同，不允许前导零
+                None
+            } else if !literal.contains(['.', 'e', 'E']) {
+                let signed = format!("{sign}{literal}");
+                signed
+                    .parse::<i64>()
+                    .map(Number::from)
+                    .ok()
+                    .or_else(|| {
+                        (!negative)
+                            .then(|| literal.parse::<u64>().ok())
+                            .flatten()
+                            .map(Number::from)
+                    })
+                    .or_else(|| signed.parse::<f64>().ok().and_then(Number::from_f64))
+            } else if literal.chars().any(|c| c.is_ascii_digit()) {
+                format!("{sign}{literal}")
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(Number::from_f64)
+            } else {
+                None
+            }
+        };
+        match value {
+            Some(n) if !self.peek().is_some_and(is_identifier_part) => Ok(Value::Number(n)),
+            _ => {
+                while self.peek().is_some_and(is_identifier_part) {
+                    self.bump();
+                }
+                let literal = &self.input[start..self.pos];
+                self.pos = start;
+                Err(self.error(format!("无效的数值 `{literal}`")))
+            }
+        }
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || matches!(c, '_' | '$')
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_json5() {
+        let input = "// 配置\n{\n  name: 'a\\\nb', /* 注释 */\n  hex: 0x1F,\n  num: [+1, .5, 5., -2e2,],\n  \"quoted\": null,\n}\n";
+        assert_eq!(
+            parse_json5(input).unwrap(),
+            json!({"name": "ab", "hex": 31, "num": [1, 0.5, 5.0, -200.0], "quoted": null})
+        );
+    }
+
+    #[test]
+    fn plain_json_is_accepted() {
+        let input = r#"{"a": [1, 2.5, "中"], "b": {"c": false}}"#;
+        assert_eq!(
+            parse_json5(input).unwrap(),
+            serde_json::from_str::<Value>(input).unwrap()
+        );
+    }
+
+    #[test]
+    fn errors_report_position() {
+        let error = parse_json5("{\n  a: Infinity\n}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        assert!(parse_json5("{a: 1} x").is_err());
+        assert!(parse_json5("/* 未结束").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! 输出前的规范化：键排序、标量数组排序、删除 null 和空容器，以及 RFC 8785（JCS）规范化输出。
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use super::Options;
+
+/// 按选项就地修改文档。删除 null 和空容器只作用于对象的成员，数组的元素保持不变
+pub fn normalize(value: &mut Value, options: &Options) {
+    match value {
+        Value::Object(map) => {
+            for child in map.values_mut() {
+                normalize(child, options);
+            }
+            if options.drop_nulls {
+                map.retain(|_, child| !child.is_null());
+            }
+            if options.drop_empty {
+                map.retain(|_, child| !is_empty_container(child));
+            }
+            if options.sort_keys {
+                map.sort_keys();
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                normalize(item, options);
+            }
+            if options.sort_arrays && items.iter().all(is_scalar) {
+                items.sort_by(compare_scalars);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// RFC 8785 规范化：键按 UTF-16 编码排序，没有空白，数值按 ECMAScript 的规则输出
+pub fn to_canonical(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+/// 把非 ASCII 字符转义为 `\uXXXX`，超出 BMP 的字符写作代理对。
+/// JSON 文本中非 ASCII 字符只会出现在字符串里，所以可以直接替换
+pub fn escape_non_ascii(json: &str) -> String {
+    let mut out = String::with_capacity(json.len());
+    for c in json.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            let mut units = [0; 2];
+            for unit in c.encode_utf16(&mut units) {
+                out.push_str(&format!("\\u{unit:04x}"));
+            }
+        }
+    }
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+        Value::Number(n) => out.push_str(&es_number(n.as_f64().unwrap_or_default())),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, child)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push(':');
+                write_canonical(child, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+/// ECMAScript 的 Number::toString：最短的可还原位数，指数在 -7 到 21 之间时不用科学计数法
+fn es_number(n: f64) -> String {
+    if n == 0.0 {
+        return "0".to_owned();
+    }
+    // `{:e}` 给出最短的可还原位数，如 `1.5e-7`
+    let exponential = format!("{:e}", n.abs());
+    let (mantissa, exponent) = exponential.split_once('e').unwrap_or((&exponential, "0"));
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n_point = exponent.parse::<i32>().unwrap_or_default() + 1;
+    let sign = if n < 0.0 { "-" } else { "" };
+    let body = if k <= n_point && n_point <= 21 {
+        format!("{digits}{}", "0".repeat((n_point - k) as usize))
+    } else if 0 < n_point && n_point <= 21 {
+        let (int, frac) = digits.split_at(n_point as usize);
+        format!("{int}.{frac}")
+    } else if -6 < n_point && n_point <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n_point as usize))
+    } else {
+        let e = n_point - 1;
+        let e = if e < 0 {
+            format!("-{}", -e)
+        } else {
+            format!("+{e}")
+        };
+        match digits.split_at(1) {
+            (first, "") => format!("{first}e{e}"),
+            (first, rest) => format!("{first}.{rest}e{e}"),
+        }
+    };
+    format!("{sign}{body}")
+}
+
+fn is_empty_container(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+/// null < 布尔值 < 数值 < 字符串，同类型按值比较
+fn compare_scalars(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            _ => 3,
+        }
+    }
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (
+                a.as_f64().unwrap_or_default(),
+                b.as_f64().unwrap_or_default(),
+            );
+            a.total_cmp(&b)
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn es_numbers() {
+        for (input, expected) in [
+            ("1e21", "1e+21"),
+            ("1e20", "100000000000000000000"),
+            ("1.5e-7", "1.5e-7"),
+            ("0.000001", "0.000001"),
+            ("100.0", "100"),
+            ("-0", "0"),
+            ("-1.25", "-1.25"),
+            ("18446744073709551615", "18446744073709552000"),
+            ("333333333.33333329", "333333333.3333333"),
+            ("4.50", "4.5"),
+            ("2e-7", "2e-7"),
+        ] {
+            let value = serde_json::from_str::<Value>(input).unwrap();
+            assert_eq!(to_canonical(&value), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn canonical_keys_sort_by_utf16() {
+        // U+1F600 的 UTF-16 代理项 0xD83D 小于 U+FB01 的 0xFB01，按 UTF-8 排序则相反
+        let value =
+            json!({"b": [1, {"z": null, "y": true}], "\u{fb01}": 1, "\u{1f600}": 2, "a": "é\n"});
+        assert_eq!(
+            to_canonical(&value),
+            "{\"a\":\"é\\n\",\"b\":[1,{\"y\":true,\"z\":null}],\"\u{1f600}\":2,\"\u{fb01}\":1}"
+        );
+    }
+
+    #[test]
+    fn escapes_non_ascii() {
+        assert_eq!(
+            escape_non_ascii(r#"{"k":"中😀a"}"#),
+            r#"{"k":"\u4e2d\ud83d\ude00a"}"#
+        );
+    }
+
+    #[test]
+    fn normalize_options() {
+        let mut value = json!({"b": [3, "x", null, 1, true], "a": {"n": null, "e": {}, "l": []}, "c": [null, []]});
+        normalize(
+            &mut value,
+            &Options {
+                sort_keys: true,
+                sort_arrays: true,
+                drop_nulls: true,
+                drop_empty: true,
+                ..Default::default()
+            },
+        );
+        // 子节点先处理，`a` 清空后也被删除；数组中的元素保持不变
+        assert_eq!(
+            value.to_string(),
+            r#"{"b":[null,true,1,3,"x"],"c":[null,[]]}"#
+        );
+    }
+}
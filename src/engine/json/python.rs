@@ -0,0 +1,707 @@
+//! Python `repr()` 字面量与 JSON 互转。
+//!
+//! 支持 dict、list、tuple、set、字符串（含 `b`/`u`/`r` 前缀、三引号和相邻字符串拼接）、
+//! int（含 `0x`/`0o`/`0b` 和下划线）、float、`True`/`False`/`None`，以及 `set()`、
+//! `Decimal('1.5')` 和 `OrderedDict([(k, v)])`。为了兼容直接粘贴的 JSON，也接受
+//! `true`/`false`/`null`。
+
+use serde_json::{Map, Number, Value};
+
+use super::SyntaxError;
+
+/// 解析 Python 字面量
+pub fn parse_python(input: &str) -> Result<Value, SyntaxError> {
+    let mut parser = Parser { input, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos < input.len() {
+        return Err(parser.error("字面量后还有多余的内容"));
+    }
+    Ok(value)
+}
+
+/// 转为 Python 字面量，`pretty` 时按 4 个空格缩进
+pub fn to_python(value: &Value, pretty: bool) -> String {
+    let mut output = String::new();
+    write_value(&mut output, value, pretty.then_some(0));
+    output
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: impl Into<String>) -> SyntaxError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> SyntaxError {
+        SyntaxError::at(self.input, pos, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += c.len_utf8();
+            } else if c == '\\' && self.rest()[1..].starts_with(['\n', '\r']) {
+                // 续行符
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SyntaxError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("应为 `{expected}`，实际为 `{c}`"))),
+            None => Err(self.error(format!("应为 `{expected}`，但输入已结束"))),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, SyntaxError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            None => Err(self.error("缺少值")),
+            Some('{') => self.dict_or_set(),
+            Some('[') => {
+                self.pos += 1;
+                Ok(Value::Array(self.items(']')?))
+            }
+            Some('(') => self.tuple(),
+            Some('\'' | '"') => self.string(),
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.identifier();
+                if matches!(self.peek(), Some('\'' | '"')) && is_string_prefix(name) {
+                    self.pos = start;
+                    return self.string();
+                }
+                self.named(name, start)
+            }
+            Some(c) => Err(self.error(format!("无法识别的字符 `{c}`"))),
+        }
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let input = self.input;
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            self.bump();
+        }
+        &input[start..self.pos]
+    }
+
+    fn named(&mut self, name: &str, start: usize) -> Result<Value, SyntaxError> {
+        let value = match name {
+            "True" | "true" => Value::Bool(true),
+            "False" | "false" => Value::Bool(false),
+            "None" | "null" => Value::Null,
+            "inf" | "nan" => {
+                return Err(self.error_at(start, format!("JSON 不支持 {name}")));
+            }
+            "set" | "frozenset" | "tuple" | "list" => {
+                self.expect('(')?;
+                self.skip_whitespace();
+                if self.peek() == Some(')') {
+                    self.pos += 1;
+                    return Ok(Value::Array(vec![]));
+                }
+                let value = self.value()?;
+                self.expect(')')?;
+                match value {
+                    Value::Array(_) => value,
+                    _ => return Err(self.error_at(start, format!("{name}() 的参数应为序列"))),
+                }
+            }
+            "Decimal" | "decimal.Decimal" => {
+                self.expect('(')?;
+                self.skip_whitespace();
+                let arg = self.pos;
+                let value = match self.value()? {
+                    Value::String(text) => parse_number(&text.replace('_', ""))
+                        .ok_or_else(|| self.error_at(arg, format!("无法转为 JSON 数值：{text}")))?,
+                    value @ Value::Number(_) => value,
+                    _ => return Err(self.error_at(arg, "Decimal() 的参数应为字符串或数值")),
+                };
+                self.expect(')')?;
+                value
+            }
+            "OrderedDict" | "collections.OrderedDict" | "dict" => {
+                self.expect('(')?;
+                self.skip_whitespace();
+                if self.peek() == Some(')') {
+                    self.pos += 1;
+                    return Ok(Value::Object(Map::new()));
+                }
+                let arg = self.pos;
+                let value = self.value()?;
+                self.expect(')')?;
+                match value {
+                    Value::Object(_) => value,
+                    Value::Array(pairs) => {
+                        let mut map = Map::new();
+                        for pair in pairs {
+                            match pair {
+                                Value::Array(pair) if pair.len() == 2 => {
+                                    let [key, value] = <[Value; 2]>::try_from(pair).unwrap();
+                                    let key = key_string(key).ok_or_else(|| {
+                                        self.error_at(arg, "键必须是字符串、数值、布尔值或 None")
+                                    })?;
+                                    map.insert(key, value);
+                                }
+                                _ => return Err(self.error_at(arg, "应为 (键, 值) 序列")),
+                            }
+                        }
+                        Value::Object(map)
+                    }
+                    _ => return Err(self.error_at(arg, "应为 dict 或 (键, 值) 序列")),
+                }
+            }
+            _ => return Err(self.error_at(start, format!("不支持的名称 `{name}`"))),
+        };
+        Ok(value)
+    }
+
+    /// 逗号分隔的值，允许末尾逗号，读到 `close` 为止
+    fn items(&mut self, close: char) -> Result<Vec<Value>, SyntaxError> {
+        let mut items = vec![];
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(items);
+            }
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(c) if c == close => {}
+                Some(c) => return Err(self.error(format!("应为 `,` 或 `{close}`，实际为 `{c}`"))),
+                None => return Err(self.error(format!("缺少 `{close}`"))),
+            }
+        }
+    }
+
+    /// `(x)` 是带括号的值，`(x,)` 和 `()` 是元组
+    fn tuple(&mut self) -> Result<Value, SyntaxError> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some(')') {
+            self.pos += 1;
+            return Ok(Value::Array(vec![]));
+        }
+        let first = self.value()?;
+        self.skip_whitespace();
+        match self.peek() {
+            Some(')') => {
+                self.pos += 1;
+                Ok(first)
+            }
+            Some(',') => {
+                self.pos += 1;
+                let mut items = vec![first];
+                items.extend(self.items(')')?);
+                Ok(Value::Array(items))
+            }
+            Some(c) => Err(self.error(format!("应为 `,` 或 `)`，实际为 `{c}`"))),
+            None => Err(self.error("缺少 `)`")),
+        }
+    }
+
+    fn dict_or_set(&mut self) -> Result<Value, SyntaxError> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Value::Object(Map::new()));
+        }
+        let key_start = self.pos;
+        let first = self.value()?;
+        self.skip_whitespace();
+        if self.peek() != Some(':') {
+            // 集合按数组处理
+            return match self.peek() {
+                Some(',') => {
+                    self.pos += 1;
+                    let mut items = vec![first];
+                    items.extend(self.items('}')?);
+                    Ok(Value::Array(items))
+                }
+                Some('}') => {
+                    self.pos += 1;
+                    Ok(Value::Array(vec![first]))
+                }
+                Some(c) => Err(self.error(format!("应为 `:`、`,` 或 `}}`，实际为 `{c}`"))),
+                None => Err(self.error("缺少 `}`")),
+            };
+        }
+
+        let mut map = Map::new();
+        let mut key = (first, key_start);
+        loop {
+            let (key_value, key_start) = key;
+            let key_text = key_string(key_value)
+                .ok_or_else(|| self.error_at(key_start, "键必须是字符串、数值、布尔值或 None"))?;
+            self.expect(':')?;
+            map.insert(key_text, self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Value::Object(map));
+                }
+                Some(c) => return Err(self.error(format!("应为 `,` 或 `}}`，实际为 `{c}`"))),
+                None => return Err(self.error("缺少 `}`")),
+            }
+            self.skip_whitespace();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Value::Object(map));
+            }
+            let start = self.pos;
+            key = (self.value()?, start);
+        }
+    }
+
+    /// 一个或多个相邻的字符串字面量
+    fn string(&mut self) -> Result<Value, SyntaxError> {
+        let mut text = String::new();
+        loop {
+            text.push_str(&self.string_literal()?);
+            let before = self.pos;
+            self.skip_whitespace();
+            let prefix_len = self
+                .rest()
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(0);
+            let after_prefix = &self.rest()[prefix_len..];
+            if !after_prefix.starts_with(['\'', '"'])
+                || !is_string_prefix(&self.rest()[..prefix_len])
+            {
+                self.pos = before;
+                return Ok(Value::String(text));
+            }
+        }
+    }
+
+    fn string_literal(&mut self) -> Result<String, SyntaxError> {
+        let start = self.pos;
+        let prefix_len = self.rest().find(['\'', '"']).unwrap_or(0);
+        let prefix = self.rest()[..prefix_len].to_ascii_lowercase();
+        let raw = prefix.contains('r');
+        let bytes = prefix.contains('b');
+        self.pos += prefix_len;
+
+        let quote = self.bump().unwrap_or('\'');
+        let triple = self.rest().starts_with(&format!("{quote}{quote}"));
+        if triple {
+            self.pos += 2;
+        }
+        // 字节串先收集字节，最后按 UTF-8 解码
+        let mut buffer = Vec::new();
+        loop {
+            let Some(c) = self.peek() else {
+                return Err(self.error_at(start, "字符串没有结束"));
+            };
+            if c == quote {
+                if !triple {
+                    self.pos += 1;
+                    break;
+                }
+                if self.rest().starts_with(&quote.to_string().repeat(3)) {
+                    self.pos += 3;
+                    break;
+                }
+            }
+            if c == '\n' && !triple {
+                return Err(self.error_at(start, "字符串没有结束"));
+            }
+            if c != '\\' || raw {
+                let mut encoded = [0; 4];
+                buffer.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+                self.pos += c.len_utf8();
+                // 原始字符串中反斜杠后的引号不结束字符串
+                if c == '\\'
+                    && let Some(next) = self.peek()
+                {
+                    buffer.extend_from_slice(next.encode_utf8(&mut encoded).as_bytes());
+                    self.pos += next.len_utf8();
+                }
+                continue;
+            }
+            self.escape(bytes, &mut buffer)?;
+        }
+        match String::from_utf8(buffer) {
+            Ok(text) => Ok(text),
+            Err(e) => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        }
+    }
+
+    fn escape(&mut self, bytes: bool, buffer: &mut Vec<u8>) -> Result<(), SyntaxError> {
+        let start = self.pos;
+        self.pos += 1;
+        let Some(c) = self.bump() else {
+            return Err(self.error_at(start, "字符串没有结束"));
+        };
+        let push_char = |buffer: &mut Vec<u8>, c: char| {
+            let mut encoded = [0; 4];
+            buffer.extend_from_slice(c.encode_utf8(&mut encoded).as_bytes());
+        };
+        match c {
+            '\n' => {}
+            '\r' => {
+                if self.peek() == Some('\n') {
+                    self.pos += 1;
+                }
+            }
+            '\\' | '\'' | '"' => push_char(buffer, c),
+            'n' => buffer.push(b'\n'),
+            'r' => buffer.push(b'\r'),
+            't' => buffer.push(b'\t'),
+            'a' => buffer.push(0x07),
+            'b' => buffer.push(0x08),
+            'f' => buffer.push(0x0c),
+            'v' => buffer.push(0x0b),
+            '0'..='7' => {
+                let mut code = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            self.pos += 1;
+                        }
+                        None => break,
+                    }
+                }
+                if bytes {
+                    buffer.push(code as u8);
+                } else {
+                    push_char(buffer, char::from_u32(code).unwrap_or('\u{fffd}'));
+                }
+            }
+            'x' | 'u' | 'U' if c == 'x' || !bytes => {
+                let len = match c {
+                    'x' => 2,
+                    'u' => 4,
+                    _ => 8,
+                };
+                let digits = self.rest().get(..len).unwrap_or_default();
+                let code = (digits.len() == len && digits.chars().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| u32::from_str_radix(digits, 16).ok())
+                    .flatten()
+                    .ok_or_else(|| {
+                        self.error_at(start, format!("`\\{c}` 后应为 {len} 位十六进制数"))
+                    })?;
+                self.pos += len;
+                if bytes {
+                    buffer.push(code as u8);
+                } else {
+                    let c = char::from_u32(code)
+                        .ok_or_else(|| self.error_at(start, "无效的 Unicode 码位"))?;
+                    push_char(buffer, c);
+                }
+            }
+            'N' if !bytes => return Err(self.error_at(start, "不支持 `\\N{...}` 转义")),
+            // 无法识别的转义保持原样
+            c => {
+                buffer.push(b'\\');
+                push_char(buffer, c);
+            }
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Value, SyntaxError> {
+        let start = self.pos;
+        let negative = match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                true
+            }
+            Some('+') => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+        self.skip_whitespace();
+        if self.peek().is_some_and(|c| c.is_alphabetic()) {
+            let name_start = self.pos;
+            let name = self.identifier();
+            return match name {
+                "inf" | "nan" => Err(self.error_at(start, format!("JSON 不支持 {name}"))),
+                _ => Err(self.error_at(name_start, "符号后应为数值")),
+            };
+        }
+        let digits_start = self.pos;
+        while let Some(c) = self.peek() {
+            let read = &self.input[digits_start..self.pos];
+            // 指数的符号，十六进制数中的 e 是数字
+            let exponent_sign = matches!(c, '+' | '-')
+                && read.ends_with(['e', 'E'])
+                && !read.to_ascii_lowercase().starts_with("0x");
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.') || exponent_sign {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let literal = self.input[digits_start..self.pos].replace('_', "");
+        let value = parse_int(&literal)
+            .map(|n| if negative { -n } else { n })
+            .and_then(|n| {
+                i64::try_from(n)
+                    .map(Number::from)
+                    .or_else(|_| u64::try_from(n).map(Number::from))
+                    .ok()
+                    .or_else(|| Number::from_f64(n as f64))
+            })
+            .map(Value::Number)
+            .or_else(|| {
+                // 不是合法整数时只能是浮点数，避免把 `01` 当作十进制数
+                if !literal.contains(['.', 'e', 'E']) {
+                    return None;
+                }
+                let signed = if negative {
+                    format!("-{literal}")
+                } else {
+                    literal.clone()
+                };
+                parse_number(&signed)
+            });
+        match value {
+            Some(value) => Ok(value),
+            None if literal.ends_with(['j', 'J']) => Err(self.error_at(start, "JSON 不支持复数")),
+            None => Err(self.error_at(
+                start,
+                format!("无效的数值 `{}`", &self.input[start..self.pos]),
+            )),
+        }
+    }
+}
+
+fn is_string_prefix(prefix: &str) -> bool {
+    matches!(
+        prefix.to_ascii_lowercase().as_str(),
+        "" | "u" | "r" | "b" | "br" | "rb"
+    )
+}
+
+/// 十进制、十六进制、八进制和二进制整数
+fn parse_int(literal: &str) -> Option<i128> {
+    let (digits, radix) = match literal.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => (&literal[2..], 16),
+        Some("0o") => (&literal[2..], 8),
+        Some("0b") => (&literal[2..], 2),
+        _ => (literal, 10),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    // Python 3 中以 0 开头的十进制整数只能是 0
+    if radix == 10 && digits.starts_with('0') && digits.bytes().any(|b| b != b'0') {
+        return None;
+    }
+    i128::from_str_radix(digits, radix).ok()
+}
+
+/// 浮点数，JSON 无法表示的值返回 `None`
+fn parse_number(literal: &str) -> Option<Value> {
+    let unsigned = literal.trim_start_matches(['-', '+']);
+    if unsigned.is_empty()
+        || !unsigned
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+    {
+        return None;
+    }
+    if let Ok(n) = literal.parse::<i64>() {
+        return Some(Value::from(n));
+    }
+    let n = literal.parse::<f64>().ok()?;
+    Number::from_f64(n).map(Value::Number)
+}
+
+/// 与 Python 的 `json.dumps` 一致，非字符串的键转为字符串
+fn key_string(key: Value) -> Option<String> {
+    match key {
+        Value::String(key) => Some(key),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some("null".to_owned()),
+        _ => None,
+    }
+}
+
+fn write_value(output: &mut String, value: &Value, indent: Option<usize>) {
+    match value {
+        Value::Null => output.push_str("None"),
+        Value::Bool(true) => output.push_str("True"),
+        Value::Bool(false) => output.push_str("False"),
+        Value::Number(n) => output.push_str(&n.to_string()),
+        Value::String(s) => write_string(output, s),
+        Value::Array(items) => {
+            write_container(output, ('[', ']'), items.iter(), indent, write_value)
+        }
+        Value::Object(map) => write_container(
+            output,
+            ('{', '}'),
+            map.iter(),
+            indent,
+            |output, (key, value), indent| {
+                write_string(output, key);
+                output.push_str(": ");
+                write_value(output, value, indent);
+            },
+        ),
+    }
+}
+
+fn write_container<T>(
+    output: &mut String,
+    (open, close): (char, char),
+    items: impl ExactSizeIterator<Item = T>,
+    indent: Option<usize>,
+    write_item: impl Fn(&mut String, T, Option<usize>),
+) {
+    output.push(open);
+    if items.len() == 0 {
+        output.push(close);
+        return;
+    }
+    let inner = indent.map(|indent| indent + 4);
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            output.push(',');
+            if inner.is_none() {
+                output.push(' ');
+            }
+        }
+        if let Some(inner) = inner {
+            output.push('\n');
+            output.push_str(&" ".repeat(inner));
+        }
+        write_item(output, item, inner);
+    }
+    if let Some(indent) = indent {
+        output.push('\n');
+        output.push_str(&" ".repeat(indent));
+    }
+    output.push(close);
+}
+
+/// 与 `repr()` 相同：优先使用单引号，只含单引号时使用双引号
+fn write_string(output: &mut String, s: &str) {
+    let quote = if s.contains('\'') && !s.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    output.push(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c == quote => {
+                output.push('\\');
+                output.push(c);
+            }
+            c if (c as u32) < 0x20 || c == '\u{7f}' => {
+                output.push_str(&format!("\\x{:02x}", c as u32));
+            }
+            c if c.is_control() || matches!(c as u32, 0x2028 | 0x2029) => {
+                output.push_str(&format!("\\u{:04x}", c as u32));
+            }
+            c => output.push(c),
+        }
+    }
+    output.push(quote);
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parses_literals() {
+        let value = parse_python(
+            "{'a': (1, 0x1F, 1_000), \"b\": [True, False, None], 3: -1.5e3, 'c': set()}",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({"a": [1, 31, 1000], "b": [true, false, null], "3": -1500.0, "c": []})
+        );
+    }
+
+    #[test]
+    fn parses_strings() {
+        assert_eq!(
+            parse_python(r#"['it\'s', "x\ty", r'\d+', b'ab' 'cd', u'中']"#).unwrap(),
+            json!(["it's", "x\ty", "\\d+", "abcd", "中"])
+        );
+        assert_eq!(parse_python("'''a\nb'''").unwrap(), json!("a\nb"));
+    }
+
+    #[test]
+    fn parses_wrappers() {
+        assert_eq!(
+            parse_python("OrderedDict([('b', 1), ('a', Decimal('1.5'))])").unwrap(),
+            json!({"b": 1, "a": 1.5})
+        );
+        assert_eq!(parse_python("{1, 2}").unwrap(), json!([1, 2]));
+    }
+
+    #[test]
+    fn errors_report_position() {
+        let error = parse_python("{'a': 1,\n 'b': nope}").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 7));
+        assert!(parse_python("[1] 2").is_err());
+        assert!(parse_python("012").is_err());
+    }
+
+    #[test]
+    fn to_python_output() {
+        let value = json!({"s": "it's", "n": null, "b": [true, 1.5], "e": {}});
+        assert_eq!(
+            to_python(&value, false),
+            r#"{'s': "it's", 'n': None, 'b': [True, 1.5], 'e': {}}"#
+        );
+        assert_eq!(
+            to_python(&json!({"a": [1]}), true),
+            "{\n    'a': [\n        1\n    ]\n}"
+        );
+        assert_eq!(parse_python(&to_python(&value, true)).unwrap(), value);
+    }
+}
@@ -0,0 +1,761 @@
+//! JSONPath 与 jq 子集查询。
+//!
+//! 支持的语法：
+//! - JSONPath：`$.a.b`、`$['a']`、`$[0]`、`$[-1]`、`$[1:3]`、`$[*]`、`$..id`、`$[?(@.n > 1)]`
+//! - jq：`.a.b`、`.[0]`、`.[]`、`..`、`|`、`[...]`、`keys`、`length`、`select(...)`、`map(...)`
+//!
+//! 条件中可以使用 `==`、`!=`、`<`、`<=`、`>`、`>=`、`and`/`&&`、`or`/`||` 和 `!`。
+//! 与 JSONPath 一致，不存在的键和类型不匹配时没有结果，而不是报错。
+
+use std::fmt;
+
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    /// 出错位置，按字符计数
+    pub offset: usize,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 个字符：{}", self.offset + 1, self.message)
+    }
+}
+
+/// 对 `value` 执行查询，返回所有结果。查询为空时返回 `value` 本身。
+pub fn query(value: &Value, query: &str) -> Result<Vec<Value>, QueryError> {
+    let tokens = lex(query)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: query.chars().count(),
+    };
+    let pipe = parser.pipe()?;
+    if let Some((_, offset)) = parser.tokens.get(parser.pos) {
+        return Err(error("多余的内容", *offset));
+    }
+    eval_pipe(&pipe, vec![value.clone()], value)
+}
+
+fn error(message: impl Into<String>, offset: usize) -> QueryError {
+    QueryError {
+        message: message.into(),
+        offset,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Dollar,
+    At,
+    Dot,
+    DotDot,
+    Star,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Pipe,
+    Colon,
+    Question,
+    Not,
+    And,
+    Or,
+    Cmp(CmpOp),
+    Ident(String),
+    Str(String),
+    Num(Value),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+fn lex(input: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '$' => Token::Dollar,
+            '@' => Token::At,
+            '.' if next == Some('.') => {
+                i += 1;
+                Token::DotDot
+            }
+            '.' => Token::Dot,
+            '*' => Token::Star,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            ':' => Token::Colon,
+            '?' => Token::Question,
+            '|' if next == Some('|') => {
+                i += 1;
+                Token::Or
+            }
+            '|' => Token::Pipe,
+            '&' if next == Some('&') => {
+                i += 1;
+                Token::And
+            }
+            '=' if next == Some('=') => {
+                i += 1;
+                Token::Cmp(CmpOp::Eq)
+            }
+            '!' if next == Some('=') => {
+                i += 1;
+                Token::Cmp(CmpOp::Ne)
+            }
+            '!' => Token::Not,
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                if or_equal {
+                    i += 1;
+                }
+                Token::Cmp(match (c, or_equal) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    (_, false) => CmpOp::Gt,
+                    (_, true) => CmpOp::Ge,
+                })
+            }
+            '"' | '\'' => {
+                let (text, end) = lex_string(&chars, i)?;
+                i = end;
+                Token::Str(text)
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut end = i + 1;
+                while end < chars.len()
+                    && (chars[end].is_ascii_digit()
+                        || matches!(chars[end], '.' | 'e' | 'E')
+                        || (matches!(chars[end], '+' | '-') && matches!(chars[end - 1], 'e' | 'E')))
+                {
+                    end += 1;
+                }
+                let text = chars[i..end].iter().collect::<String>();
+                let number = serde_json::from_str::<Value>(&text)
+                    .ok()
+                    .filter(Value::is_number)
+                    .ok_or_else(|| error(format!("无效的数字：{text}"), start))?;
+                i = end - 1;
+                Token::Num(number)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = i + 1;
+                while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+                    end += 1;
+                }
+                let word = chars[i..end].iter().collect::<String>();
+                i = end - 1;
+                match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    _ => Token::Ident(word),
+                }
+            }
+            other => return Err(error(format!("无法识别的字符 {other}"), start)),
+        };
+        tokens.push((token, start));
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+/// 读取引号包围的字符串，返回内容和结束引号的位置
+fn lex_string(chars: &[char], start: usize) -> Result<(String, usize), QueryError> {
+    let quote = chars[start];
+    let mut text = String::new();
+    let mut i = start + 1;
+    while let Some(&c) = chars.get(i) {
+        match c {
+            c if c == quote => return Ok((text, i)),
+            '\\' => {
+                i += 1;
+                let escaped = chars.get(i).ok_or_else(|| error("字符串没有结束", start))?;
+                text.push(match escaped {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    other => *other,
+                });
+            }
+            c => text.push(c),
+        }
+        i += 1;
+    }
+    Err(error("字符串没有结束", start))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    Key(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>),
+    Wildcard,
+    /// 自身及所有后代
+    Descendants,
+    /// 保留满足条件的子元素
+    Filter(Cond),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Path {
+        relative_to_root: bool,
+        steps: Vec<Step>,
+    },
+    Collect(Vec<Expr>),
+    Keys(usize),
+    Length(usize),
+    Select(Box<Cond>),
+    Map(Vec<Expr>),
+    Literal(Value),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Cond {
+    Value(Expr),
+    Cmp(Expr, CmpOp, Expr),
+    And(Box<Cond>, Box<Cond>),
+    Or(Box<Cond>, Box<Cond>),
+    Not(Box<Cond>),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    /// 查询的字符数，用于报告结尾处的错误
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.end, |(_, offset)| *offset)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token, what: &str) -> Result<(), QueryError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(error(format!("缺少 {what}"), self.offset()))
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Vec<Expr>, QueryError> {
+        let mut exprs = vec![];
+        if self.peek().is_none() {
+            return Ok(exprs);
+        }
+        loop {
+            exprs.push(self.term()?);
+            if !self.eat(&Token::Pipe) {
+                return Ok(exprs);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, QueryError> {
+        let offset = self.offset();
+        match self.peek().cloned() {
+            Some(Token::Dollar) => {
+                self.pos += 1;
+                Ok(Expr::Path {
+                    relative_to_root: true,
+                    steps: self.steps()?,
+                })
+            }
+            Some(Token::At | Token::Dot | Token::DotDot) => {
+                if self.peek() == Some(&Token::At) {
+                    self.pos += 1;
+                }
+                Ok(Expr::Path {
+                    relative_to_root: false,
+                    steps: self.steps()?,
+                })
+            }
+            Some(Token::LBracket) => {
+                self.pos += 1;
+                let inner = self.pipe()?;
+                self.expect(&Token::RBracket, "]")?;
+                Ok(Expr::Collect(inner))
+            }
+            Some(Token::Str(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(Value::String(s)))
+            }
+            Some(Token::Num(n)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(n))
+            }
+            Some(Token::Ident(word)) => {
+                self.pos += 1;
+                match word.as_str() {
+                    "true" => Ok(Expr::Literal(Value::Bool(true))),
+                    "false" => Ok(Expr::Literal(Value::Bool(false))),
+                    "null" => Ok(Expr::Literal(Value::Null)),
+                    "keys" => Ok(Expr::Keys(offset)),
+                    "length" => Ok(Expr::Length(offset)),
+                    "select" => {
+                        self.expect(&Token::LParen, "(")?;
+                        let cond = self.cond()?;
+                        self.expect(&Token::RParen, ")")?;
+                        Ok(Expr::Select(Box::new(cond)))
+                    }
+                    "map" => {
+                        self.expect(&Token::LParen, "(")?;
+                        let inner = self.pipe()?;
+                        self.expect(&Token::RParen, ")")?;
+                        Ok(Expr::Map(inner))
+                    }
+                    other => Err(error(format!("不支持的函数 {other}"), offset)),
+                }
+            }
+            Some(_) => Err(error("此处应为路径或函数", offset)),
+            None => Err(error("查询不完整", offset)),
+        }
+    }
+
+    /// 路径的各级，可以以 `.`、`..` 或 `[` 开始
+    fn steps(&mut self) -> Result<Vec<Step>, QueryError> {
+        let mut steps = vec![];
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.pos += 1;
+                    self.member(&mut steps);
+                }
+                Some(Token::DotDot) => {
+                    self.pos += 1;
+                    steps.push(Step::Descendants);
+                    self.member(&mut steps);
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    steps.push(self.bracket()?);
+                }
+                _ => return Ok(steps),
+            }
+        }
+    }
+
+    /// `.` 之后的键名或 `*`，可以省略（如 jq 的 `.` 和 `..`）
+    fn member(&mut self, steps: &mut Vec<Step>) {
+        match self.peek().cloned() {
+            Some(Token::Ident(name) | Token::Str(name)) => {
+                self.pos += 1;
+                steps.push(Step::Key(name));
+            }
+            Some(Token::Star) => {
+                self.pos += 1;
+                steps.push(Step::Wildcard);
+            }
+            _ => {}
+        }
+    }
+
+    /// `[` 之后的内容
+    fn bracket(&mut self) -> Result<Step, QueryError> {
+        let offset = self.offset();
+        let step = match self.peek().cloned() {
+            Some(Token::RBracket) => Step::Wildcard,
+            Some(Token::Star) => {
+                self.pos += 1;
+                Step::Wildcard
+            }
+            Some(Token::Str(key)) => {
+                self.pos += 1;
+                Step::Key(key)
+            }
+            Some(Token::Question) => {
+                self.pos += 1;
+                self.expect(&Token::LParen, "(")?;
+                let cond = self.cond()?;
+                self.expect(&Token::RParen, ")")?;
+                Step::Filter(cond)
+            }
+            Some(Token::Num(_) | Token::Colon) => {
+                let start = self.integer()?;
+                if self.eat(&Token::Colon) {
+                    let end = self.integer()?;
+                    Step::Slice(start, end)
+                } else {
+                    Step::Index(start.ok_or_else(|| error("缺少下标", offset))?)
+                }
+            }
+            _ => return Err(error("此处应为下标、键名、* 或过滤条件", offset)),
+        };
+        self.expect(&Token::RBracket, "]")?;
+        Ok(step)
+    }
+
+    fn integer(&mut self) -> Result<Option<i64>, QueryError> {
+        let offset = self.offset();
+        match self.peek() {
+            Some(Token::Num(n)) => {
+                let index = n.as_i64().ok_or_else(|| error("下标必须是整数", offset))?;
+                self.pos += 1;
+                Ok(Some(index))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn cond(&mut self) -> Result<Cond, QueryError> {
+        let mut cond = self.and_cond()?;
+        while self.eat(&Token::Or) {
+            cond = Cond::Or(Box::new(cond), Box::new(self.and_cond()?));
+        }
+        Ok(cond)
+    }
+
+    fn and_cond(&mut self) -> Result<Cond, QueryError> {
+        let mut cond = self.unary_cond()?;
+        while self.eat(&Token::And) {
+            cond = Cond::And(Box::new(cond), Box::new(self.unary_cond()?));
+        }
+        Ok(cond)
+    }
+
+    fn unary_cond(&mut self) -> Result<Cond, QueryError> {
+        if self.eat(&Token::Not) {
+            return Ok(Cond::Not(Box::new(self.unary_cond()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let cond = self.cond()?;
+            self.expect(&Token::RParen, ")")?;
+            return Ok(cond);
+        }
+        let left = self.term()?;
+        match self.peek() {
+            Some(&Token::Cmp(op)) => {
+                self.pos += 1;
+                Ok(Cond::Cmp(left, op, self.term()?))
+            }
+            _ => Ok(Cond::Value(left)),
+        }
+    }
+}
+
+fn eval_pipe(pipe: &[Expr], input: Vec<Value>, root: &Value) -> Result<Vec<Value>, QueryError> {
+    let mut values = input;
+    for expr in pipe {
+        let mut next = vec![];
+        for value in &values {
+            next.extend(eval(expr, value, root)?);
+        }
+        values = next;
+    }
+    Ok(values)
+}
+
+fn eval(expr: &Expr, value: &Value, root: &Value) -> Result<Vec<Value>, QueryError> {
+    Ok(match expr {
+        Expr::Path {
+            relative_to_root,
+            steps,
+        } => {
+            let start = if *relative_to_root { root } else { value };
+            let mut current = vec![start];
+            for step in steps {
+                let mut next = vec![];
+                for value in current {
+                    apply_step(step, value, root, &mut next)?;
+                }
+                current = next;
+            }
+            current.into_iter().cloned().collect()
+        }
+        Expr::Collect(inner) => vec![Value::Array(eval_pipe(inner, vec![value.clone()], root)?)],
+        Expr::Keys(offset) => match value {
+            Value::Object(map) => {
+                let mut keys = map.keys().cloned().map(Value::String).collect::<Vec<_>>();
+                keys.sort_by(|a, b| a.as_str().cmp(&b.as_str()));
+                vec![Value::Array(keys)]
+            }
+            Value::Array(items) => vec![Value::Array((0..items.len()).map(Value::from).collect())],
+            _ => return Err(error("keys 只能用于对象或数组", *offset)),
+        },
+        Expr::Length(offset) => vec![match value {
+            Value::Null => Value::from(0),
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+            Value::Number(n) => n
+                .as_i64()
+                .map(|n| Value::from(n.unsigned_abs()))
+                .or_else(|| n.as_f64().map(|n| Value::from(n.abs())))
+                .unwrap_or(Value::Null),
+            Value::Bool(_) => return Err(error("布尔值没有 length", *offset)),
+        }],
+        Expr::Select(cond) => {
+            if test(cond, value, root)? {
+                vec![value.clone()]
+            } else {
+                vec![]
+            }
+        }
+        Expr::Map(inner) => {
+            let items = match value {
+                Value::Array(items) => items.clone(),
+                Value::Object(map) => map.values().cloned().collect(),
+                _ => vec![],
+            };
+            vec![Value::Array(eval_pipe(inner, items, root)?)]
+        }
+        Expr::Literal(literal) => vec![literal.clone()],
+    })
+}
+
+fn apply_step<'a>(
+    step: &Step,
+    value: &'a Value,
+    root: &Value,
+    out: &mut Vec<&'a Value>,
+) -> Result<(), QueryError> {
+    match step {
+        Step::Key(key) => out.extend(value.as_object().and_then(|map| map.get(key))),
+        Step::Index(index) => {
+            if let Value::Array(items) = value {
+                let index = if *index < 0 {
+                    items.len() as i64 + index
+                } else {
+                    *index
+                };
+                out.extend(usize::try_from(index).ok().and_then(|i| items.get(i)));
+            }
+        }
+        Step::Slice(start, end) => {
+            if let Value::Array(items) = value {
+                let len = items.len() as i64;
+                let clamp = |i: i64| (if i < 0 { len + i } else { i }).clamp(0, len) as usize;
+                let start = clamp(start.unwrap_or(0));
+                let end = clamp(end.unwrap_or(len));
+                if start < end {
+                    out.extend(&items[start..end]);
+                }
+            }
+        }
+        Step::Wildcard => out.extend(children(value)),
+        Step::Descendants => {
+            let mut stack = vec![value];
+            while let Some(value) = stack.pop() {
+                out.push(value);
+                let mut kids = children(value).collect::<Vec<_>>();
+                kids.reverse();
+                stack.extend(kids);
+            }
+        }
+        Step::Filter(cond) => {
+            for child in children(value) {
+                if test(cond, child, root)? {
+                    out.push(child);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn children(value: &Value) -> Box<dyn Iterator<Item = &Value> + '_> {
+    match value {
+        Value::Array(items) => Box::new(items.iter()),
+        Value::Object(map) => Box::new(map.values()),
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+fn test(cond: &Cond, value: &Value, root: &Value) -> Result<bool, QueryError> {
+    Ok(match cond {
+        Cond::Value(expr) => eval(expr, value, root)?.first().is_some_and(truthy),
+        Cond::Cmp(left, op, right) => {
+            let left = eval(left, value, root)?;
+            let right = eval(right, value, root)?;
+            match (left.first(), right.first()) {
+                (Some(left), Some(right)) => compare(left, *op, right),
+                // 不存在的值只与 `!=` 匹配
+                _ => *op == CmpOp::Ne,
+            }
+        }
+        Cond::And(a, b) => test(a, value, root)? && test(b, value, root)?,
+        Cond::Or(a, b) => test(a, value, root)? || test(b, value, root)?,
+        Cond::Not(cond) => !test(cond, value, root)?,
+    })
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn compare(left: &Value, op: CmpOp, right: &Value) -> bool {
+    use std::cmp::Ordering;
+
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+        (a, b) if a == b => Some(Ordering::Equal),
+        _ => None,
+    };
+    match op {
+        CmpOp::Eq => ordering == Some(Ordering::Equal),
+        CmpOp::Ne => ordering != Some(Ordering::Equal),
+        CmpOp::Lt => ordering == Some(Ordering::Less),
+        CmpOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CmpOp::Gt => ordering == Some(Ordering::Greater),
+        CmpOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+/// 将 `root` 中的 JSON Pointer（如 `/items/0/id`）转为可以查询的 JSONPath（如 `$.items[0].id`）。
+///
+/// 按 `root` 中的容器类型区分数组下标和全是数字的键；路径在 `root` 中不存在时，全是数字的段按下标处理
+pub fn path_from_pointer(root: &Value, pointer: &str) -> String {
+    let mut path = String::from("$");
+    let mut node = Some(root);
+    for segment in pointer.split('/').skip(1) {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        let (is_index, child) = match node {
+            Some(Value::Array(items)) => match segment.parse::<usize>() {
+                Ok(index) => (true, items.get(index)),
+                Err(_) => (false, None),
+            },
+            Some(Value::Object(map)) => (false, map.get(&segment)),
+            _ => (
+                !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()),
+                None,
+            ),
+        };
+        node = child;
+        if is_index {
+            path.push_str(&format!("[{segment}]"));
+        } else if segment.starts_with(|c: char| c.is_alphabetic() || c == '_')
+            && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        {
+            path.push('.');
+            path.push_str(&segment);
+        } else {
+            let escaped = segment.replace('\\', "\\\\").replace('\'', "\\'");
+            path.push_str(&format!("['{escaped}']"));
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn doc() -> Value {
+        json!({
+            "store": {
+                "books": [
+                    {"title": "A", "price": 8, "tags": ["x"]},
+                    {"title": "B", "price": 12},
+                    {"title": "C", "price": 20, "tags": []}
+                ],
+                "name": "shop"
+            },
+            "weird key": 1
+        })
+    }
+
+    fn run(q: &str) -> Vec<Value> {
+        query(&doc(), q).unwrap_or_else(|e| panic!("{q}: {e}"))
+    }
+
+    #[test]
+    fn jsonpath() {
+        assert_eq!(run("$.store.name"), [json!("shop")]);
+        assert_eq!(run("$['weird key']"), [json!(1)]);
+        assert_eq!(run("$.store.books[-1].title"), [json!("C")]);
+        assert_eq!(run("$.store.books[0:2].title"), [json!("A"), json!("B")]);
+        assert_eq!(run("$.store.books[*].price").len(), 3);
+        assert_eq!(run("$..title"), [json!("A"), json!("B"), json!("C")]);
+        assert_eq!(
+            run("$.store.books[?(@.price > 10 && @.tags)].title"),
+            [json!("C")]
+        );
+        assert!(run("$.missing.key").is_empty());
+    }
+
+    #[test]
+    fn jq() {
+        assert_eq!(run(".store.books[1].price"), [json!(12)]);
+        assert_eq!(
+            run(".store.books[] | select(.price >= 12) | .title"),
+            [json!("B"), json!("C")]
+        );
+        assert_eq!(run("[.store.books[].price]"), [json!([8, 12, 20])]);
+        assert_eq!(run(".store | keys"), [json!(["books", "name"])]);
+        assert_eq!(run(".store.books | length"), [json!(3)]);
+        assert_eq!(run(".store.books | map(.title)"), [json!(["A", "B", "C"])]);
+        assert_eq!(run(""), [doc()]);
+    }
+
+    #[test]
+    fn errors_have_offsets() {
+        let error = query(&doc(), ".store[").unwrap_err();
+        assert_eq!(error.offset, 7);
+        let error = query(&doc(), ".store | length | keys").unwrap_err();
+        assert!(error.message.contains("keys"), "{error}");
+        assert!(query(&doc(), "$.a b").is_err());
+    }
+
+    #[test]
+    fn pointer_to_path() {
+        let value = json!({"items": [{"id": 1}], "a/b": {"it's": 1}, "0": {"1": [true]}});
+        assert_eq!(path_from_pointer(&value, ""), "$");
+        assert_eq!(path_from_pointer(&value, "/items/0/id"), "$.items[0].id");
+        assert_eq!(
+            path_from_pointer(&value, "/a~1b/it's"),
+            r"$['a/b']['it\'s']"
+        );
+        // 全是数字的键不是下标
+        assert_eq!(path_from_pointer(&value, "/0/1/0"), "$['0']['1'][0]");
+        // 不存在的路径按段的写法猜测
+        assert_eq!(path_from_pointer(&value, "/x/2"), "$.x[2]");
+        for pointer in ["/a~1b/it's", "/0/1/0"] {
+            let path = path_from_pointer(&value, pointer);
+            assert_eq!(
+                query(&value, &path).unwrap(),
+                [value.pointer(pointer).unwrap().clone()],
+                "{path}"
+            );
+        }
+    }
+}
@@ -0,0 +1,273 @@
+//! JSON Schema（draft 2020-12）校验与推断。
+//!
+//! 推断时同一位置的多个值会合并：类型取并集，对象中不是每次都出现的键不列入 `required`。
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate};
+use serde_json::{Map, Value, json};
+
+const DRAFT_2020_12: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// 一处校验失败
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    /// 出错值的 JSON Pointer，根节点为空字符串
+    pub pointer: String,
+    pub message: String,
+}
+
+/// 按 draft 2020-12 校验，同时校验 `format`。Schema 本身无效时返回错误
+pub fn validate_schema(schema: &Value, instance: &Value) -> Result<Vec<Violation>, String> {
+    let validator = jsonschema::draft202012::options()
+        .should_validate_formats(true)
+        .build(schema)
+        .map_err(|e| format!("Schema 无效：{e}"))?;
+    Ok(validator
+        .iter_errors(instance)
+        .map(|e| Violation {
+            pointer: e.instance_path().as_str().to_owned(),
+            message: e.to_string(),
+        })
+        .collect())
+}
+
+/// 从示例文档推断 Schema
+pub fn infer_schema(sample: &Value) -> Value {
+    let mut shape = Shape::default();
+    shape.add(sample);
+    let mut schema = Map::new();
+    schema.insert("$schema".to_owned(), Value::from(DRAFT_2020_12));
+    if let Value::Object(inferred) = shape.to_schema() {
+        schema.extend(inferred);
+    }
+    Value::Object(schema)
+}
+
+/// 同一位置出现过的所有值的类型
+#[derive(Clone, Debug, Default)]
+pub(super) struct Shape {
+    pub(super) null: bool,
+    pub(super) boolean: bool,
+    pub(super) integer: bool,
+    pub(super) number: bool,
+    pub(super) string: Option<StringShape>,
+    pub(super) object: Option<ObjectShape>,
+    /// 数组元素合并后的类型
+    pub(super) array: Option<Box<Shape>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct StringShape {
+    /// 所有字符串都符合的格式
+    pub(super) format: Option<&'static str>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(super) struct ObjectShape {
+    /// 出现过的对象个数
+    pub(super) count: usize,
+    /// 每个键的类型和出现次数
+    pub(super) properties: BTreeMap<String, (Shape, usize)>,
+}
+
+impl ObjectShape {
+    /// 键是否在每个对象中都出现
+    pub(super) fn is_required(&self, key: &str) -> bool {
+        self.properties
+            .get(key)
+            .is_some_and(|(_, count)| *count == self.count)
+    }
+}
+
+impl Shape {
+    pub(super) fn add(&mut self, value: &Value) {
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            Value::Number(n) if n.is_f64() => self.number = true,
+            Value::Number(_) => self.integer = true,
+            Value::String(s) => {
+                let format = string_format(s);
+                match &mut self.string {
+                    Some(string) => {
+                        if string.format != format {
+                            string.format = None;
+                        }
+                    }
+                    None => self.string = Some(StringShape { format }),
+                }
+            }
+            Value::Array(items) => {
+                let shape = self.array.get_or_insert_default();
+                for item in items {
+                    shape.add(item);
+                }
+            }
+            Value::Object(map) => {
+                let object = self.object.get_or_insert_default();
+                object.count += 1;
+                for (key, value) in map {
+                    let (shape, count) = object.properties.entry(key.clone()).or_default();
+                    shape.add(value);
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    /// 没有出现过任何值，如空数组的元素
+    pub(super) fn is_empty(&self) -> bool {
+        !self.null
+            && !self.boolean
+            && !self.integer
+            && !self.number
+            && self.string.is_none()
+            && self.object.is_none()
+            && self.array.is_none()
+    }
+
+    fn to_schema(&self) -> Value {
+        let mut schema = Map::new();
+        let mut types = vec![];
+        if let Some(object) = &self.object {
+            types.push("object");
+            let properties = object
+                .properties
+                .iter()
+                .map(|(key, (shape, _))| (key.clone(), shape.to_schema()))
+                .collect::<Map<_, _>>();
+            let required = object
+                .properties
+                .keys()
+                .filter(|key| object.is_required(key))
+                .map(|key| Value::from(key.as_str()))
+                .collect::<Vec<_>>();
+            if !properties.is_empty() {
+                schema.insert("properties".to_owned(), Value::Object(properties));
+            }
+            if !required.is_empty() {
+                schema.insert("required".to_owned(), Value::Array(required));
+            }
+        }
+        if let Some(items) = &self.array {
+            types.push("array");
+            if !items.is_empty() {
+                schema.insert("items".to_owned(), items.to_schema());
+            }
+        }
+        if let Some(string) = &self.string {
+            types.push("string");
+            if let Some(format) = string.format {
+                schema.insert("format".to_owned(), Value::from(format));
+            }
+        }
+        // 整数也是 number
+        if self.number {
+            types.push("number");
+        } else if self.integer {
+            types.push("integer");
+        }
+        if self.boolean {
+            types.push("boolean");
+        }
+        if self.null {
+            types.push("null");
+        }
+        match types.as_slice() {
+            [] => {}
+            [ty] => {
+                schema.insert("type".to_owned(), json!(ty));
+            }
+            _ => {
+                schema.insert("type".to_owned(), json!(types));
+            }
+        }
+        Value::Object(schema)
+    }
+}
+
+/// 识别常见的字符串格式
+fn string_format(s: &str) -> Option<&'static str> {
+    if DateTime::parse_from_rfc3339(s).is_ok() {
+        Some("date-time")
+    } else if s.len() == 10 && NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok() {
+        Some("date")
+    } else if is_uuid(s) {
+        Some("uuid")
+    } else {
+        None
+    }
+}
+
+fn is_uuid(s: &str) -> bool {
+    let groups = s.split('-').collect::<Vec<_>>();
+    groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12])
+        && groups
+            .iter()
+            .all(|g| g.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infers_merged_shapes() {
+        let sample = json!([
+            {"id": 1, "at": "2024-01-02T03:04:05Z", "tags": ["a"]},
+            {"id": 2.5, "at": "2024-01-03T00:00:00+08:00", "note": null, "tags": []}
+        ]);
+        assert_eq!(
+            infer_schema(&sample),
+            json!({
+                "$schema": DRAFT_2020_12,
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "at": {"type": "string", "format": "date-time"},
+                        "id": {"type": "number"},
+                        "note": {"type": "null"},
+                        "tags": {"type": "array", "items": {"type": "string"}}
+                    },
+                    "required": ["at", "id", "tags"]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn string_formats() {
+        assert_eq!(string_format("2024-02-29"), Some("date"));
+        assert_eq!(
+            string_format("123e4567-e89b-12d3-a456-426614174000"),
+            Some("uuid")
+        );
+        assert_eq!(string_format("2024-02-30"), None);
+        let schema = infer_schema(&json!(["2024-01-01", "x"]));
+        assert_eq!(schema["items"], json!({"type": "string"}));
+    }
+
+    #[test]
+    fn inferred_schema_accepts_sample() {
+        let sample = json!({"user": {"name": "a", "mail": null}, "n": [1, 2]});
+        assert_eq!(validate_schema(&infer_schema(&sample), &sample), Ok(vec![]));
+    }
+
+    #[test]
+    fn violations_have_pointers() {
+        let schema = json!({
+            "type": "object",
+            "properties": {"items": {"type": "array", "items": {"type": "integer"}}, "day": {"format": "date"}}
+        });
+        let violations =
+            validate_schema(&schema, &json!({"items": [1, "x"], "day": "2024-13-01"})).unwrap();
+        let pointers = violations
+            .iter()
+            .map(|v| v.pointer.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(pointers, ["/items/1", "/day"]);
+        assert!(validate_schema(&json!({"type": 1}), &json!(null)).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+//! 带位置的解析错误。
+
+use std::fmt;
+
+/// 解析错误，行列号从 1 开始，列按字符计数
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl SyntaxError {
+    /// `pos` 为 `input` 中的字节偏移，落在字符中间或超出末尾时取之前最近的字符边界
+    pub(super) fn at(input: &str, pos: usize, message: impl Into<String>) -> Self {
+        let mut pos = pos.min(input.len());
+        while !input.is_char_boundary(pos) {
+            pos -= 1;
+        }
+        let before = &input[..pos];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            message: message.into(),
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+
+    /// 转换 serde_json 的错误，列号从字节数换算为字符数
+    pub fn from_serde(input: &str, error: &serde_json::Error) -> Self {
+        let message = error.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(end) => message[..end].to_owned(),
+            None => message,
+        };
+        let (line, column) = (error.line().max(1), error.column());
+        let column = input.lines().nth(line - 1).map_or(column.max(1), |text| {
+            text.char_indices()
+                .take_while(|&(offset, _)| offset + 1 < column)
+                .count()
+                + 1
+        });
+        Self {
+            message,
+            line,
+            column,
+        }
+    }
+}
+
+impl SyntaxError {
+    /// 出错位置在 `input` 中的字符下标，用于移动光标
+    pub fn char_index(&self, input: &str) -> usize {
+        let line_start: usize = input
+            .split('\n')
+            .take(self.line - 1)
+            .map(|line| line.chars().count() + 1)
+            .sum();
+        (line_start + self.column - 1).min(input.chars().count())
+    }
+
+    /// 按常见错误给出修改建议，没有匹配的规则时返回 `None`
+    pub fn hint(&self, input: &str) -> Option<&'static str> {
+        let offset = input
+            .char_indices()
+            .nth(self.char_index(input))
+            .map_or(input.len(), |(offset, _)| offset);
+        let (before, after) = input.split_at(offset);
+        let at = after.chars().next();
+        let previous = before.trim_end().chars().last();
+        let word = after
+            .split(|c: char| !c.is_alphanumeric())
+            .next()
+            .unwrap_or_default();
+
+        if self.message.contains("control character") || self.message.contains("不能直接换行")
+        {
+            Some("字符串中不能直接换行，需要写作 \\n")
+        } else if at == Some('\'') {
+            Some("JSON 的字符串和键需要使用双引号；Python dict 可以勾选「Python dict 风格」")
+        } else if matches!(at, Some('}' | ']')) && previous == Some(',') {
+            Some("JSON 不允许末尾逗号，删除最后一个逗号，或使用 JSON5 / JSONC 模式")
+        } else if after.starts_with("//") || after.starts_with("/*") {
+            Some("JSON 不支持注释，可以使用 JSON5 / JSONC 模式")
+        } else if matches!(word, "None" | "True" | "False") {
+            Some("None、True 和 False 是 Python 的写法，可以勾选「Python dict 风格」")
+        } else if at.is_some_and(|c| c.is_alphabetic() || c == '_') {
+            Some("键和字符串需要加双引号；JSON5 / JSONC 模式允许不加引号的键")
+        } else if self.message.contains("trailing characters") {
+            Some("输入中有多个 JSON 文档；每行一个文档时可以使用 NDJSON 模式")
+        } else if self.message.contains("EOF")
+            || self.message.contains("没有结束")
+            || after.trim().is_empty()
+        {
+            Some("输入不完整，可能被截断了，检查括号和引号是否成对")
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "第 {} 行第 {} 列：{}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn position_counts_chars() {
+        let error = SyntaxError::at("{\n  \"中文\": x", 14, "错误");
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(error.to_string(), "第 2 行第 9 列：错误");
+        // 字节偏移落在“中”的中间或超出末尾
+        let error = SyntaxError::at("a\n中", 3, "错误");
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = SyntaxError::at("a\n中", 10, "错误");
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+
+    #[test]
+    fn from_serde_converts_columns() {
+        let input = "{\"中\": x}";
+        let error = serde_json::from_str::<serde_json::Value>(input).unwrap_err();
+        let error = SyntaxError::from_serde(input, &error);
+        assert_eq!((error.line, error.column), (1, 7));
+        assert!(!error.message.contains(" at line "));
+    }
+
+    fn error(input: &str) -> SyntaxError {
+        let e = serde_json::from_str::<serde_json::Value>(input).unwrap_err();
+        SyntaxError::from_serde(input, &e)
+    }
+
+    #[test]
+    fn char_index_points_at_error() {
+        let input = "{\n  \"中\": x}";
+        let error = error(input);
+        assert_eq!(input.chars().nth(error.char_index(input)), Some('x'));
+    }
+
+    #[test]
+    fn hints() {
+        let hint = |input| error(input).hint(input);
+        assert_eq!(
+            hint("{'a': 1}"),
+            Some("JSON 的字符串和键需要使用双引号；Python dict 可以勾选「Python dict 风格」")
+        );
+        assert_eq!(
+            hint("[1, 2,]"),
+            Some("JSON 不允许末尾逗号，删除最后一个逗号，或使用 JSON5 / JSONC 模式")
+        );
+        assert_eq!(
+            hint("{\"a\": None}"),
+            Some("None、True 和 False 是 Python 的写法，可以勾选「Python dict 风格」")
+        );
+        assert_eq!(
+            hint("{\"a\": [1, 2"),
+            Some("输入不完整，可能被截断了，检查括号和引号是否成对")
+        );
+        assert_eq!(
+            hint("{} {}"),
+            Some("输入中有多个 JSON 文档；每行一个文档时可以使用 NDJSON 模式")
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! 与界面无关的转换逻辑。
+//!
+//! 各工具面板只负责展示，实际的转换都在这里完成，便于脚本复用和单元测试。
+
+pub mod detect;
+pub mod json;
+pub mod stack;
+pub mod timestamp;
+pub mod url;
@@ -0,0 +1,80 @@
+//! 堆栈字符串的分行处理。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 输入中转义换行符的形式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineFormat {
+    #[default]
+    Lf,
+    Crlf,
+}
+
+impl LineFormat {
+    fn escaped(&self) -> &'static str {
+        match self {
+            LineFormat::Lf => "\\n",
+            LineFormat::Crlf => "\\r\\n",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub text: String,
+    /// 是否为 `file:line` 形式的代码行
+    pub is_code: bool,
+}
+
+/// 将转义的换行符、制表符还原，并按行拆分。
+pub fn split(input: &str, format: LineFormat) -> Vec<Line> {
+    input
+        .replace(format.escaped(), "\n")
+        .replace("\\t", "\t")
+        .lines()
+        .map(|line| Line {
+            text: line.to_owned(),
+            is_code: is_code_line(line),
+        })
+        .collect()
+}
+
+/// 与 [`split`] 相同，但直接拼接为文本，每行以 `\n` 结尾。
+pub fn format(input: &str, format: LineFormat) -> String {
+    split(input, format)
+        .into_iter()
+        .map(|line| line.text + "\n")
+        .collect()
+}
+
+pub fn is_code_line(line: &str) -> bool {
+    static LINE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\w+:\d+( \+\w+)?").unwrap());
+    LINE_PATTERN.is_match(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_escaped_newlines() {
+        let lines = split("panic\\n\\tat main.rs:12\\ndone", LineFormat::Lf);
+        let texts = lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(texts, ["panic", "\tat main.rs:12", "done"]);
+        assert_eq!(
+            lines.iter().map(|line| line.is_code).collect::<Vec<_>>(),
+            [false, true, false]
+        );
+    }
+
+    #[test]
+    fn crlf_format() {
+        assert_eq!(format("a\\r\\nb", LineFormat::Crlf), "a\nb\n");
+        assert_eq!(format("a\\r\\nb", LineFormat::Lf), "a\\r\nb\n");
+    }
+}
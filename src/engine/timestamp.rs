@@ -0,0 +1,345 @@
+//! 时间戳与时间字符串的互相转换。
+
+use std::fmt;
+
+use chrono::{LocalResult, TimeDelta, prelude::*};
+use serde::{Deserialize, Serialize};
+
+mod batch;
+mod duration;
+mod epoch;
+mod number;
+mod parse;
+mod render;
+mod zone;
+
+pub use batch::{BatchMode, BatchRow, convert_batch, to_tsv};
+pub use duration::{Difference, difference, humanize, parse_duration, parse_instant, shift};
+pub use epoch::{ALL_EPOCHS, Epoch};
+pub use parse::{Parsed, ParsedTime, parse as parse_datetime};
+pub use render::{Rendering, clock, format_custom, relative, renderings};
+pub use zone::{COMMON_ZONES, Zone};
+
+pub const DT_FORMAT_S: &str = "%F %T";
+pub const DT_FORMAT_MS: &str = "%F %T%.3f";
+pub const DT_FORMAT_US: &str = "%F %T%.6f";
+pub const DT_FORMAT_NS: &str = "%F %T%.9f";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeUnit {
+    Sec,
+    #[default]
+    Milli,
+    Micro,
+    Nano,
+}
+
+impl TimeUnit {
+    /// 该精度下的时间字符串格式
+    pub fn dt_format(&self) -> &'static str {
+        match self {
+            TimeUnit::Sec => DT_FORMAT_S,
+            TimeUnit::Milli => DT_FORMAT_MS,
+            TimeUnit::Micro => DT_FORMAT_US,
+            TimeUnit::Nano => DT_FORMAT_NS,
+        }
+    }
+
+    /// 每个单位包含的纳秒数
+    pub fn nanos(&self) -> i64 {
+        match self {
+            TimeUnit::Sec => 1_000_000_000,
+            TimeUnit::Milli => 1_000_000,
+            TimeUnit::Micro => 1_000,
+            TimeUnit::Nano => 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// 可以转换，但位数不是常见的 10/13/16/19 位
+    NonStandard,
+    Invalid,
+    ParseFailed,
+    NoLocalTime,
+    /// 超出 chrono 可表示的范围（约公元前 262000 年至公元 262000 年）
+    OutOfRange,
+}
+
+impl Warning {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Warning::NonStandard => "⚠ 非标准时间戳格式（秒/毫秒/微秒/纳秒）",
+            Warning::Invalid => "⚠ 无效输入",
+            Warning::ParseFailed => "时间解析失败",
+            Warning::NoLocalTime => "该时间在所选时区中不存在或有歧义",
+            Warning::OutOfRange => "⚠ 超出可表示的时间范围（约公元前 26 万年至公元 26 万年）",
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Converted {
+    pub output: String,
+    pub warning: Option<Warning>,
+    /// 输入对应的时刻，可用于在其他时区展示
+    pub instant: Option<DateTime<Utc>>,
+    /// 输入为时间字符串时匹配到的格式
+    pub format: Option<&'static str>,
+}
+
+impl Converted {
+    fn ok(output: String, instant: DateTime<Utc>) -> Self {
+        Self {
+            output,
+            warning: None,
+            instant: Some(instant),
+            format: None,
+        }
+    }
+
+    fn warn(warning: Warning) -> Self {
+        Self {
+            output: String::new(),
+            warning: Some(warning),
+            instant: None,
+            format: None,
+        }
+    }
+}
+
+/// 时间戳转为 `zone` 时区的时间字符串，或将时间字符串转为时间戳。
+///
+/// 超过 10 位的时间戳按 `unit` 的精度输出；时间字符串按 `unit` 输出对应单位的时间戳，
+/// 支持的格式见 [`parse_datetime`]，不带偏移时按 `zone` 解释。
+pub fn convert(input: &str, unit: TimeUnit, zone: Zone) -> Converted {
+    let input = input.trim();
+    if input.is_empty() {
+        return Converted::default();
+    }
+
+    if let Some(parsed) = parse_datetime(input) {
+        let mut converted = match parsed.time {
+            ParsedTime::Absolute(dt) => to_timestamp(dt, unit),
+            ParsedTime::Local(dt) => match zone.from_local(&dt) {
+                LocalResult::Single(dt) => to_timestamp(dt, unit),
+                _ => Converted::warn(Warning::NoLocalTime),
+            },
+        };
+        converted.format = Some(parsed.format);
+        return converted;
+    }
+
+    let Some(number) = number::parse(input) else {
+        return Converted::warn(Warning::Invalid);
+    };
+    // 超过 10 位的整数部分视为毫秒、微秒或纳秒时间戳
+    let unit = if number.int_digits() > 10 || number.has_fraction() {
+        unit
+    } else {
+        TimeUnit::Sec
+    };
+    let mut converted = match number.to_delta(Some(10)) {
+        Ok(delta) => from_delta(delta, unit, zone),
+        Err(warning) => Converted::warn(warning),
+    };
+    if converted.warning.is_none() && number.plain && !matches!(input.len(), 10 | 13 | 16 | 19) {
+        converted.warning = Some(Warning::NonStandard);
+    }
+    converted
+}
+
+/// 与 [`convert`] 相同，但数值按 `epoch` 的时间基准解释，时间字符串输出为该基准下的数值。
+pub fn convert_epoch(input: &str, epoch: Epoch, unit: TimeUnit, zone: Zone) -> Converted {
+    let input = input.trim();
+    if epoch == Epoch::Unix {
+        return convert(input, unit, zone);
+    }
+    if input.is_empty() {
+        return Converted::default();
+    }
+    if let Some(parsed) = parse_datetime(input) {
+        let instant = match parsed.time {
+            ParsedTime::Absolute(dt) => dt.to_utc(),
+            ParsedTime::Local(dt) => match zone.from_local(&dt) {
+                LocalResult::Single(dt) => dt.to_utc(),
+                _ => return Converted::warn(Warning::NoLocalTime),
+            },
+        };
+        let mut converted = Converted::ok(epoch.from_instant(instant, zone), instant);
+        converted.format = Some(parsed.format);
+        return converted;
+    }
+    match epoch.to_instant(input, zone) {
+        Ok(instant) => Converted::ok(format_in(instant, zone, unit), instant),
+        Err(warning) => Converted::warn(warning),
+    }
+}
+
+fn to_timestamp(dt: DateTime<FixedOffset>, unit: TimeUnit) -> Converted {
+    let instant = dt.to_utc();
+    match timestamp_in(instant, unit) {
+        Some(ts) => Converted::ok(ts.to_string(), instant),
+        None => Converted::warn(Warning::OutOfRange),
+    }
+}
+
+fn from_delta(delta: TimeDelta, unit: TimeUnit, zone: Zone) -> Converted {
+    match DateTime::UNIX_EPOCH.checked_add_signed(delta) {
+        Some(instant) => Converted::ok(format_in(instant, zone, unit), instant),
+        None => Converted::warn(Warning::OutOfRange),
+    }
+}
+
+/// 以 `unit` 的精度输出 `zone` 时区的时间字符串
+pub fn format_in(instant: DateTime<Utc>, zone: Zone, unit: TimeUnit) -> String {
+    zone.from_utc(&instant.naive_utc())
+        .format(unit.dt_format())
+        .to_string()
+}
+
+/// 当前时间，返回 `(时间戳, zone 时区的时间字符串)`，两者都使用 `unit` 的精度。
+pub fn now(unit: TimeUnit, zone: Zone) -> (String, String) {
+    let now = Utc::now();
+    let timestamp = timestamp_in(now, unit).unwrap_or_default();
+    (timestamp.to_string(), format_in(now, zone, unit))
+}
+
+/// 以 `unit` 为单位的时间戳，纳秒时间戳只能表示 1677～2262 年
+pub fn timestamp_in(instant: DateTime<Utc>, unit: TimeUnit) -> Option<i64> {
+    match unit {
+        TimeUnit::Sec => Some(instant.timestamp()),
+        TimeUnit::Milli => Some(instant.timestamp_millis()),
+        TimeUnit::Micro => Some(instant.timestamp_micros()),
+        TimeUnit::Nano => instant.timestamp_nanos_opt(),
+    }
+}
+
+/// 纯数字输入按位数对应的单位
+pub(crate) fn unit_for(input: &str) -> Option<TimeUnit> {
+    if input.is_empty() || !input.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(match input.len() {
+        ..=10 => TimeUnit::Sec,
+        11..=13 => TimeUnit::Milli,
+        14..=16 => TimeUnit::Micro,
+        _ => TimeUnit::Nano,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamp_round_trips_through_local_time() {
+        for (input, unit) in [
+            ("1700000000", TimeUnit::Sec),
+            ("1700000000123", TimeUnit::Milli),
+            ("1700000000123456", TimeUnit::Micro),
+            ("1700000000123456789", TimeUnit::Nano),
+        ] {
+            let local = convert(input, unit, Zone::Local);
+            assert_eq!(local.warning, None, "{input}");
+            assert_eq!(convert(&local.output, unit, Zone::Local).output, input);
+        }
+    }
+
+    #[test]
+    fn converts_in_zone() {
+        let shanghai = "Asia/Shanghai".parse().unwrap();
+        let converted = convert("1700000000", TimeUnit::Sec, shanghai);
+        assert_eq!(converted.output, "2023-11-15 06:13:20");
+        assert_eq!(
+            convert("2023-11-14 22:13:20.000", TimeUnit::Milli, Zone::Utc).output,
+            "1700000000000"
+        );
+        assert_eq!(
+            convert("2023-11-15 06:13:20.000", TimeUnit::Sec, shanghai).output,
+            "1700000000"
+        );
+    }
+
+    #[test]
+    fn converts_other_epochs() {
+        let converted = convert_epoch("1384035218", Epoch::Gps, TimeUnit::Sec, Zone::Utc);
+        assert_eq!(converted.output, "2023-11-14 22:13:20");
+        let converted = convert_epoch(
+            "2023-11-14 22:13:20",
+            Epoch::Cocoa,
+            TimeUnit::Sec,
+            Zone::Utc,
+        );
+        assert_eq!(converted.output, "721692800");
+        assert_eq!(converted.format, Some("%F %T"));
+        assert_eq!(
+            convert_epoch("x", Epoch::Excel, TimeUnit::Sec, Zone::Utc).warning,
+            Some(Warning::Invalid)
+        );
+    }
+
+    #[test]
+    fn signed_decimal_and_scientific_timestamps() {
+        let output = |input| convert(input, TimeUnit::Milli, Zone::Utc).output;
+        assert_eq!(output("1.7e9"), "2023-11-14 22:13:20");
+        assert_eq!(output("1700000000.5"), "2023-11-14 22:13:20.500");
+        assert_eq!(output("-86400"), "1969-12-31 00:00:00");
+        assert_eq!(
+            convert("1e30", TimeUnit::Sec, Zone::Utc).warning,
+            Some(Warning::OutOfRange)
+        );
+        assert_eq!(
+            convert("1700000000.5", TimeUnit::Sec, Zone::Utc).warning,
+            None
+        );
+    }
+
+    #[test]
+    fn output_precision_follows_unit() {
+        let local = convert("1700000000123", TimeUnit::Micro, Zone::Local).output;
+        assert!(local.ends_with(".123000"), "{local}");
+    }
+
+    #[test]
+    fn unusual_lengths_are_non_standard() {
+        assert_eq!(
+            convert("12345", TimeUnit::Sec, Zone::Local).warning,
+            Some(Warning::NonStandard)
+        );
+        assert_eq!(
+            convert("17000000001", TimeUnit::Milli, Zone::Local).warning,
+            Some(Warning::NonStandard)
+        );
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert_eq!(
+            convert("", TimeUnit::Sec, Zone::Local),
+            Converted::default()
+        );
+        assert_eq!(
+            convert("abc", TimeUnit::Sec, Zone::Local).warning,
+            Some(Warning::Invalid)
+        );
+        assert_eq!(
+            convert("2023-13-01", TimeUnit::Sec, Zone::Local).warning,
+            Some(Warning::Invalid)
+        );
+    }
+
+    #[test]
+    fn offset_in_input_overrides_zone() {
+        let converted = convert("2023-11-15T06:13:20+08:00", TimeUnit::Sec, Zone::Utc);
+        assert_eq!(converted.output, "1700000000");
+        assert_eq!(converted.format, Some("RFC 3339"));
+    }
+}
@@ -0,0 +1,124 @@
+//! 批量转换多行文本中的时间戳或时间字符串。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{TimeUnit, Warning, Zone, convert, unit_for};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BatchMode {
+    /// 每行是一个值，空行保留以便与表格对齐
+    #[default]
+    Lines,
+    /// 从任意文本中提取时间戳和时间字符串
+    Tokens,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BatchRow {
+    /// 从 1 开始的行号
+    pub line: usize,
+    pub input: String,
+    pub output: String,
+    pub warning: Option<Warning>,
+}
+
+/// 逐个转换，纯数字按位数推断输出精度，时间字符串按 `unit` 输出
+pub fn convert_batch(text: &str, mode: BatchMode, unit: TimeUnit, zone: Zone) -> Vec<BatchRow> {
+    let convert_one = |line, input: &str| {
+        let converted = convert(input, unit_for(input).unwrap_or(unit), zone);
+        BatchRow {
+            line,
+            input: input.to_owned(),
+            output: converted.output,
+            warning: converted.warning,
+        }
+    };
+    let lines = text.lines().enumerate().map(|(i, line)| (i + 1, line));
+    match mode {
+        BatchMode::Lines => lines
+            .map(|(line, input)| convert_one(line, input.trim()))
+            .collect(),
+        BatchMode::Tokens => lines
+            .flat_map(|(line, input)| {
+                TOKEN
+                    .find_iter(input)
+                    .map(move |m| convert_one(line, m.as_str()))
+            })
+            .collect(),
+    }
+}
+
+/// 每行为 `输入\t输出\t警告`，可直接粘贴到表格中
+pub fn to_tsv(rows: &[BatchRow]) -> String {
+    rows.iter()
+        .map(|row| {
+            let warning = row.warning.map_or("", |w| w.message());
+            format!("{}\t{}\t{warning}\n", row.input, row.output)
+        })
+        .collect()
+}
+
+/// 10～19 位时间戳，或 `%F %T` 及 ISO 8601 形式的时间字符串
+static TOKEN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"\b\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}([.,]\d+)?(Z|[+-]\d{2}:?\d{2})?|\b\d{10}(\d{3}|\d{6}|\d{9})?\b",
+    )
+    .unwrap()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_keep_blank_rows() {
+        let rows = convert_batch(
+            "1700000000\n\n1700000000123\nabc",
+            BatchMode::Lines,
+            TimeUnit::Sec,
+            Zone::Utc,
+        );
+        let outputs = rows
+            .iter()
+            .map(|row| row.output.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            ["2023-11-14 22:13:20", "", "2023-11-14 22:13:20.123", ""]
+        );
+        assert_eq!(rows[3].warning, Some(Warning::Invalid));
+        assert_eq!(rows[3].line, 4);
+    }
+
+    #[test]
+    fn tokens_are_extracted_from_text() {
+        let rows = convert_batch(
+            "start=1700000000123 end=2023-11-14T22:13:20Z id=42\nnothing here",
+            BatchMode::Tokens,
+            TimeUnit::Sec,
+            Zone::Utc,
+        );
+        let pairs = rows
+            .iter()
+            .map(|row| (row.line, row.input.as_str(), row.output.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            pairs,
+            [
+                (1, "1700000000123", "2023-11-14 22:13:20.123"),
+                (1, "2023-11-14T22:13:20Z", "1700000000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn tsv() {
+        let rows = convert_batch("1700000000\nx", BatchMode::Lines, TimeUnit::Sec, Zone::Utc);
+        assert_eq!(
+            to_tsv(&rows),
+            "1700000000\t2023-11-14 22:13:20\t\nx\t\t⚠ 无效输入\n"
+        );
+    }
+}
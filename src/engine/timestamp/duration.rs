@@ -0,0 +1,183 @@
+//! 时刻之间的差值，以及时刻加减时长。
+
+use chrono::{TimeDelta, prelude::*};
+
+use super::{TimeUnit, Zone, convert, unit_for};
+
+/// 时间差在各单位下的值，超出 i64 范围时为 `None`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub delta: TimeDelta,
+    pub secs: i64,
+    pub millis: i64,
+    pub micros: Option<i64>,
+    pub nanos: Option<i64>,
+}
+
+impl Difference {
+    pub fn in_unit(&self, unit: TimeUnit) -> Option<i64> {
+        match unit {
+            TimeUnit::Sec => Some(self.secs),
+            TimeUnit::Milli => Some(self.millis),
+            TimeUnit::Micro => self.micros,
+            TimeUnit::Nano => self.nanos,
+        }
+    }
+}
+
+/// 解析时间戳（按位数推断单位）或时间字符串（不带偏移时按 `zone` 解释）
+pub fn parse_instant(input: &str, zone: Zone) -> Result<DateTime<Utc>, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("请输入时间".to_owned());
+    }
+    let converted = convert(input, unit_for(input).unwrap_or_default(), zone);
+    match (converted.instant, converted.warning) {
+        (Some(instant), _) => Ok(instant),
+        (None, Some(warning)) => Err(warning.to_string()),
+        (None, None) => Err("无法识别的时间".to_owned()),
+    }
+}
+
+/// `end - start`
+pub fn difference(start: DateTime<Utc>, end: DateTime<Utc>) -> Difference {
+    let delta = end.signed_duration_since(start);
+    Difference {
+        delta,
+        secs: delta.num_seconds(),
+        millis: delta.num_milliseconds(),
+        micros: delta.num_microseconds(),
+        nanos: delta.num_nanoseconds(),
+    }
+}
+
+/// 解析 `+1h30m`、`-250ms`、`2d 4h` 形式的时长，不带符号时为正。
+///
+/// 单位：`w` 周、`d` 天、`h` 小时、`m` 分钟、`s` 秒、`ms` 毫秒、`us`/`µs` 微秒、`ns` 纳秒。
+pub fn parse_duration(input: &str) -> Result<TimeDelta, String> {
+    let input = input.trim();
+    let (negative, mut rest) = match input.as_bytes().first() {
+        Some(b'-') => (true, &input[1..]),
+        Some(b'+') => (false, &input[1..]),
+        _ => (false, input),
+    };
+    rest = rest.trim_start();
+    if rest.is_empty() {
+        return Err("请输入时长，如 +1h30m 或 -250ms".to_owned());
+    }
+
+    let mut total = TimeDelta::zero();
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return Err(format!("缺少数值：{rest}"));
+        }
+        let value = rest[..digits]
+            .parse::<i64>()
+            .map_err(|_| format!("数值过大：{}", &rest[..digits]))?;
+        rest = &rest[digits..];
+        let unit_len = rest.len() - rest.trim_start_matches(char::is_alphabetic).len();
+        let (unit, tail) = rest.split_at(unit_len);
+        let delta = match unit {
+            "w" => TimeDelta::try_weeks(value),
+            "d" => TimeDelta::try_days(value),
+            "h" => TimeDelta::try_hours(value),
+            "m" | "min" => TimeDelta::try_minutes(value),
+            "s" => TimeDelta::try_seconds(value),
+            "ms" => TimeDelta::try_milliseconds(value),
+            "us" | "µs" => Some(TimeDelta::microseconds(value)),
+            "ns" => Some(TimeDelta::nanoseconds(value)),
+            "" => return Err(format!("{value} 缺少单位")),
+            other => return Err(format!("未知的时间单位：{other}")),
+        };
+        total = delta
+            .and_then(|delta| total.checked_add(&delta))
+            .ok_or("时长超出范围")?;
+        rest = tail.trim_start();
+    }
+    Ok(if negative { -total } else { total })
+}
+
+/// `instant` 加上 `delta`，结果超出范围时返回 `None`
+pub fn shift(instant: DateTime<Utc>, delta: TimeDelta) -> Option<DateTime<Utc>> {
+    instant.checked_add_signed(delta)
+}
+
+/// 如“-1 天 2 小时 30 分钟 250 毫秒”，省略为零的部分
+pub fn humanize(delta: TimeDelta) -> String {
+    if delta.is_zero() {
+        return "0 秒".to_owned();
+    }
+    let sign = if delta < TimeDelta::zero() { "-" } else { "" };
+    let secs = delta.num_seconds().unsigned_abs();
+    let nanos = delta.subsec_nanos().unsigned_abs();
+    let parts = [
+        (secs / 86_400, "天"),
+        (secs % 86_400 / 3600, "小时"),
+        (secs % 3600 / 60, "分钟"),
+        (secs % 60, "秒"),
+        (u64::from(nanos / 1_000_000), "毫秒"),
+        (u64::from(nanos / 1_000 % 1_000), "微秒"),
+        (u64::from(nanos % 1_000), "纳秒"),
+    ];
+    let text = parts
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{value} {unit}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("{sign}{text}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("+1h30m"), Ok(TimeDelta::minutes(90)));
+        assert_eq!(parse_duration("-250ms"), Ok(TimeDelta::milliseconds(-250)));
+        assert_eq!(parse_duration("2d 4h"), Ok(TimeDelta::hours(52)));
+        assert_eq!(
+            parse_duration("1w1s1us1ns"),
+            Ok(TimeDelta::weeks(1)
+                + TimeDelta::seconds(1)
+                + TimeDelta::microseconds(1)
+                + TimeDelta::nanoseconds(1))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("5").is_err());
+        assert!(parse_duration("5y").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+    }
+
+    #[test]
+    fn difference_in_units() {
+        let start = parse_instant("1700000000", Zone::Utc).unwrap();
+        let end = parse_instant("2023-11-14T23:13:20.5Z", Zone::Utc).unwrap();
+        let diff = difference(start, end);
+        assert_eq!(diff.secs, 3600);
+        assert_eq!(diff.in_unit(TimeUnit::Milli), Some(3_600_500));
+        assert_eq!(difference(end, start).secs, -3600);
+        assert!(parse_instant("", Zone::Utc).is_err());
+        assert!(parse_instant("abc", Zone::Utc).is_err());
+    }
+
+    #[test]
+    fn shift_and_humanize() {
+        let start = parse_instant("1700000000", Zone::Utc).unwrap();
+        let shifted = shift(start, TimeDelta::days(1)).unwrap();
+        assert_eq!(shifted.timestamp(), 1700086400);
+        assert_eq!(shift(DateTime::<Utc>::MAX_UTC, TimeDelta::seconds(1)), None);
+        assert_eq!(humanize(TimeDelta::zero()), "0 秒");
+        assert_eq!(
+            humanize(-(TimeDelta::hours(26) + TimeDelta::milliseconds(250))),
+            "-1 天 2 小时 250 毫秒"
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! Unix 以外的时间基准：GPS、Windows FILETIME、Apple Cocoa、Excel 序列日期和 ROS。
+
+use std::str::FromStr;
+
+use chrono::{LocalResult, TimeDelta, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{Warning, Zone, number};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Epoch {
+    #[default]
+    Unix,
+    /// 1980-01-06 起的秒数，不含闰秒
+    Gps,
+    /// 1601-01-01 起的 100 纳秒数
+    FileTime,
+    /// 2001-01-01 起的秒数，即 `NSDate` 的 reference date
+    Cocoa,
+    /// 1900 日期系统的序列日期，小数部分为一天中的时间，按所选时区解释
+    Excel,
+    /// `secs.nsecs`，纳秒部分固定 9 位
+    Ros,
+}
+
+pub const ALL_EPOCHS: &[Epoch] = &[
+    Epoch::Unix,
+    Epoch::Gps,
+    Epoch::FileTime,
+    Epoch::Cocoa,
+    Epoch::Excel,
+    Epoch::Ros,
+];
+
+const GPS_EPOCH: i64 = 315_964_800;
+const FILETIME_EPOCH: i64 = -11_644_473_600;
+const COCOA_EPOCH: i64 = 978_307_200;
+
+/// 1980 年以来插入闰秒后的第一秒（Unix 时间），来自 IERS 公告。
+///
+/// 新增闰秒时在末尾追加即可；IERS 已宣布 2035 年前不再插入闰秒。
+const LEAP_SECONDS: &[i64] = &[
+    362_793_600,   // 1981-07-01
+    394_329_600,   // 1982-07-01
+    425_865_600,   // 1983-07-01
+    489_024_000,   // 1985-07-01
+    567_993_600,   // 1988-01-01
+    631_152_000,   // 1990-01-01
+    662_688_000,   // 1991-01-01
+    709_948_800,   // 1992-07-01
+    741_484_800,   // 1993-07-01
+    773_020_800,   // 1994-07-01
+    820_454_400,   // 1996-01-01
+    867_715_200,   // 1997-07-01
+    915_148_800,   // 1999-01-01
+    1_136_073_600, // 2006-01-01
+    1_230_768_000, // 2009-01-01
+    1_341_100_800, // 2012-07-01
+    1_435_708_800, // 2015-07-01
+    1_483_228_800, // 2017-01-01
+];
+
+impl Epoch {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Epoch::Unix => "Unix",
+            Epoch::Gps => "GPS",
+            Epoch::FileTime => "Windows FILETIME",
+            Epoch::Cocoa => "Apple Cocoa",
+            Epoch::Excel => "Excel 序列日期",
+            Epoch::Ros => "ROS secs.nsecs",
+        }
+    }
+
+    /// 解析该基准下的数值
+    pub fn to_instant(&self, input: &str, zone: Zone) -> Result<DateTime<Utc>, Warning> {
+        let input = input.trim();
+        match self {
+            Epoch::Unix => seconds(input).and_then(|delta| offset(0, delta)),
+            Epoch::Gps => {
+                let gps = seconds(input)?;
+                let leaps = LEAP_SECONDS
+                    .iter()
+                    .enumerate()
+                    .take_while(|&(i, &leap)| {
+                        TimeDelta::seconds(leap - GPS_EPOCH + i as i64 + 1) <= gps
+                    })
+                    .count();
+                offset(GPS_EPOCH, gps - TimeDelta::seconds(leaps as i64))
+            }
+            Epoch::FileTime => {
+                let ticks = input.parse::<i64>().map_err(|_| Warning::Invalid)?;
+                offset(FILETIME_EPOCH, ticks_delta(ticks))
+            }
+            Epoch::Cocoa => seconds(input).and_then(|delta| offset(COCOA_EPOCH, delta)),
+            Epoch::Excel => excel_to_instant(input, zone),
+            Epoch::Ros => ros_seconds(input).and_then(|delta| offset(0, delta)),
+        }
+    }
+
+    /// 输出该基准下的数值
+    pub fn from_instant(&self, instant: DateTime<Utc>, zone: Zone) -> String {
+        let since = |epoch: i64| instant.signed_duration_since(unix(epoch));
+        match self {
+            Epoch::Unix => format_seconds(since(0)),
+            Epoch::Gps => format_seconds(gps_delta(instant)),
+            Epoch::FileTime => {
+                let delta = since(FILETIME_EPOCH);
+                match delta.num_microseconds() {
+                    Some(micros) => {
+                        let ticks = micros * 10 + i64::from(delta.subsec_nanos() % 1000 / 100);
+                        ticks.to_string()
+                    }
+                    None => "超出范围".to_owned(),
+                }
+            }
+            Epoch::Cocoa => format_seconds(since(COCOA_EPOCH)),
+            Epoch::Excel => excel_from_instant(instant, zone),
+            Epoch::Ros => {
+                // 与读取时一致，按带符号的小数输出，保留全部 9 位纳秒
+                let delta = since(0);
+                let sign = if delta < TimeDelta::zero() { "-" } else { "" };
+                let delta = delta.abs();
+                format!("{sign}{}.{:09}", delta.num_seconds(), delta.subsec_nanos())
+            }
+        }
+    }
+
+    /// 根据数值的形态猜测时间基准，只识别不易与 Unix 时间戳混淆的情况
+    pub fn guess(input: &str) -> Option<Epoch> {
+        let input = input.trim();
+        if input.contains("secs") {
+            return Some(Epoch::Ros);
+        }
+        let (int, frac) = input.split_once('.').unwrap_or((input, ""));
+        if int.is_empty() || !int.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        if !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        match (int.len(), frac.len()) {
+            // 1981～2077 年
+            (18, 0) if matches!(&int[..2], "12" | "13" | "14" | "15") => Some(Epoch::FileTime),
+            // 1954～2119 年
+            (5, _) if (20_000..80_000).contains(&int.parse::<u32>().ok()?) => Some(Epoch::Excel),
+            (10, 9) => Some(Epoch::Ros),
+            _ => None,
+        }
+    }
+
+    /// GPS 周数与周内秒
+    pub fn gps_week(instant: DateTime<Utc>) -> (i64, f64) {
+        let gps = gps_delta(instant);
+        let week = gps.num_seconds().div_euclid(604_800);
+        let rest = gps - TimeDelta::weeks(week);
+        (week, rest.num_milliseconds() as f64 / 1000.0)
+    }
+}
+
+impl FromStr for Epoch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "unix" => Ok(Epoch::Unix),
+            "gps" => Ok(Epoch::Gps),
+            "filetime" | "windows" => Ok(Epoch::FileTime),
+            "cocoa" | "apple" => Ok(Epoch::Cocoa),
+            "excel" => Ok(Epoch::Excel),
+            "ros" => Ok(Epoch::Ros),
+            _ => Err(format!("未知的时间基准：{s}")),
+        }
+    }
+}
+
+fn unix(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
+
+fn offset(epoch: i64, delta: TimeDelta) -> Result<DateTime<Utc>, Warning> {
+    unix(epoch)
+        .checked_add_signed(delta)
+        .ok_or(Warning::OutOfRange)
+}
+
+/// GPS 时间领先 UTC 的秒数随闰秒增加
+fn gps_delta(instant: DateTime<Utc>) -> TimeDelta {
+    let leaps = LEAP_SECONDS
+        .iter()
+        .take_while(|&&leap| leap <= instant.timestamp())
+        .count();
+    instant.signed_duration_since(unix(GPS_EPOCH)) + TimeDelta::seconds(leaps as i64)
+}
+
+fn ticks_delta(ticks: i64) -> TimeDelta {
+    TimeDelta::microseconds(ticks / 10) + TimeDelta::nanoseconds(ticks % 10 * 100)
+}
+
+/// 解析带可选符号、小数或指数的秒数
+fn seconds(input: &str) -> Result<TimeDelta, Warning> {
+    number::parse(input).ok_or(Warning::Invalid)?.to_delta(None)
+}
+
+/// `secs.nsecs`，或 `rostopic echo` 输出的 `secs: ... nsecs: ...`
+fn ros_seconds(input: &str) -> Result<TimeDelta, Warning> {
+    if input.contains("secs") {
+        let mut secs = None;
+        let mut nsecs = None;
+        let mut tokens = input.split(|c: char| c.is_whitespace() || c == ',' || c == ':');
+        while let Some(token) = tokens.next() {
+            let target = match token.trim_matches(|c| c == '"' || c == '{' || c == '}') {
+                "secs" | "sec" => &mut secs,
+                "nsecs" | "nanosec" => &mut nsecs,
+                _ => continue,
+            };
+            *target = tokens
+                .find(|t| !t.is_empty())
+                .and_then(|t| t.parse::<i64>().ok());
+        }
+        let (Some(secs), Some(nsecs)) = (secs, nsecs) else {
+            return Err(Warning::Invalid);
+        };
+        return Ok(TimeDelta::try_seconds(secs).ok_or(Warning::OutOfRange)?
+            + TimeDelta::nanoseconds(nsecs));
+    }
+    seconds(input)
+}
+
+/// 1900 日期系统中 1900-03-01 之前的序列号受“1900 年是闰年”的错误影响
+fn excel_to_instant(input: &str, zone: Zone) -> Result<DateTime<Utc>, Warning> {
+    let serial = input.parse::<f64>().map_err(|_| Warning::Invalid)?;
+    if !(0.0..2_958_466.0).contains(&serial) {
+        return Err(Warning::OutOfRange);
+    }
+    if (60.0..61.0).contains(&serial) {
+        // Excel 中不存在的 1900-02-29
+        return Err(Warning::Invalid);
+    }
+    let base = if serial < 60.0 {
+        NaiveDate::from_ymd_opt(1899, 12, 31)
+    } else {
+        NaiveDate::from_ymd_opt(1899, 12, 30)
+    }
+    .ok_or(Warning::ParseFailed)?
+    .and_time(NaiveTime::MIN);
+    let millis = (serial * 86_400_000.0).round() as i64;
+    let local = base + TimeDelta::milliseconds(millis);
+    match zone.from_local(&local) {
+        LocalResult::Single(dt) => Ok(dt.to_utc()),
+        _ => Err(Warning::NoLocalTime),
+    }
+}
+
+fn excel_from_instant(instant: DateTime<Utc>, zone: Zone) -> String {
+    let local = zone.from_utc(&instant.naive_utc()).naive_local();
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)
+        .unwrap_or_default()
+        .and_time(NaiveTime::MIN);
+    let millis = local.signed_duration_since(base).num_milliseconds();
+    let mut serial = millis as f64 / 86_400_000.0;
+    if serial < 61.0 {
+        serial -= 1.0;
+    }
+    if serial < 0.0 {
+        return "超出范围".to_owned();
+    }
+    trim_fraction(format!("{serial:.8}"))
+}
+
+fn format_seconds(delta: TimeDelta) -> String {
+    let negative = delta < TimeDelta::zero();
+    let delta = delta.abs();
+    let text = trim_fraction(format!(
+        "{}.{:09}",
+        delta.num_seconds(),
+        delta.subsec_nanos()
+    ));
+    if negative { format!("-{text}") } else { text }
+}
+
+fn trim_fraction(text: String) -> String {
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_owned()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_time(NaiveTime::MIN)
+            .and_utc()
+    }
+
+    #[test]
+    fn gps_counts_leap_seconds() {
+        assert_eq!(Epoch::Gps.from_instant(at(GPS_EPOCH), Zone::Utc), "0");
+        assert_eq!(
+            Epoch::Gps.from_instant(at(1700000000), Zone::Utc),
+            "1384035218"
+        );
+        // 2016-12-31 插入第 18 个闰秒，前后相差 2 秒
+        let before = Epoch::Gps.from_instant(at(1_483_228_799), Zone::Utc);
+        let after = Epoch::Gps.from_instant(at(1_483_228_800), Zone::Utc);
+        assert_eq!(
+            (before.as_str(), after.as_str()),
+            ("1167264016", "1167264018")
+        );
+        for (gps, unix) in [("1167264016", 1_483_228_799), ("1167264018", 1_483_228_800)] {
+            assert_eq!(Epoch::Gps.to_instant(gps, Zone::Utc), Ok(at(unix)));
+        }
+        assert_eq!(Epoch::gps_week(at(1700000000)), (2288, 252818.0));
+    }
+
+    #[test]
+    fn filetime_and_cocoa() {
+        let instant = at(1700000000);
+        assert_eq!(
+            Epoch::FileTime.from_instant(instant, Zone::Utc),
+            "133444736000000000"
+        );
+        assert_eq!(
+            Epoch::FileTime.to_instant("133444736000000001", Zone::Utc),
+            Ok(instant + TimeDelta::nanoseconds(100))
+        );
+        assert_eq!(Epoch::Cocoa.from_instant(instant, Zone::Utc), "721692800");
+        assert_eq!(
+            Epoch::Cocoa.to_instant("-0.5", Zone::Utc),
+            Ok(at(COCOA_EPOCH) - TimeDelta::milliseconds(500))
+        );
+    }
+
+    #[test]
+    fn excel_1900_leap_year_bug() {
+        let excel = |serial| Epoch::Excel.to_instant(serial, Zone::Utc);
+        assert_eq!(excel("1"), Ok(utc(1900, 1, 1)));
+        assert_eq!(excel("59"), Ok(utc(1900, 2, 28)));
+        // Excel 认为存在 1900-02-29
+        assert_eq!(excel("60"), Err(Warning::Invalid));
+        assert_eq!(excel("61"), Ok(utc(1900, 3, 1)));
+        assert_eq!(
+            excel("45000.5"),
+            Ok(utc(2023, 3, 15) + TimeDelta::hours(12))
+        );
+        for (date, serial) in [(utc(1900, 2, 28), "59"), (utc(1900, 3, 1), "61")] {
+            assert_eq!(Epoch::Excel.from_instant(date, Zone::Utc), serial);
+        }
+        let shanghai = "Asia/Shanghai".parse().unwrap();
+        assert_eq!(
+            Epoch::Excel.to_instant("45000", shanghai),
+            Ok(utc(2023, 3, 15) - TimeDelta::hours(8))
+        );
+    }
+
+    #[test]
+    fn ros() {
+        let instant = at(1700000000) + TimeDelta::nanoseconds(123_456_789);
+        assert_eq!(
+            Epoch::Ros.from_instant(instant, Zone::Utc),
+            "1700000000.123456789"
+        );
+        assert_eq!(
+            Epoch::Ros.to_instant("secs: 1700000000\nnsecs: 123456789", Zone::Utc),
+            Ok(instant)
+        );
+        let before = at(0) - TimeDelta::milliseconds(500);
+        assert_eq!(Epoch::Ros.from_instant(before, Zone::Utc), "-0.500000000");
+        assert_eq!(Epoch::Ros.to_instant("-0.500000000", Zone::Utc), Ok(before));
+        let before = at(-2) - TimeDelta::nanoseconds(1);
+        let text = Epoch::Ros.from_instant(before, Zone::Utc);
+        assert_eq!(text, "-2.000000001");
+        assert_eq!(Epoch::Ros.to_instant(&text, Zone::Utc), Ok(before));
+        assert_eq!(
+            Epoch::Ros.to_instant("secs: 1", Zone::Utc),
+            Err(Warning::Invalid)
+        );
+    }
+
+    #[test]
+    fn guesses_epoch() {
+        assert_eq!(Epoch::guess("133444736000000000"), Some(Epoch::FileTime));
+        assert_eq!(Epoch::guess("45000.5"), Some(Epoch::Excel));
+        assert_eq!(Epoch::guess("1700000000.123456789"), Some(Epoch::Ros));
+        assert_eq!(Epoch::guess("1700000000"), None);
+        assert_eq!(Epoch::guess("abc"), None);
+    }
+}
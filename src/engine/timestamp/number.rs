@@ -0,0 +1,181 @@
+//! 时间戳数值的词法分析，支持符号、小数和科学计数法。
+
+use chrono::TimeDelta;
+
+use super::Warning;
+
+/// 指数的绝对值上限，超过时一定超出时间范围
+const MAX_EXPONENT: i32 = 64;
+
+/// 十进制数值，整数部分已去掉前导零，小数部分保留全部位数
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct Number {
+    pub(super) negative: bool,
+    pub(super) int: String,
+    pub(super) frac: String,
+    /// 只由数字组成，没有符号、小数点和指数
+    pub(super) plain: bool,
+}
+
+/// 解析 `[+-]digits[.digits][(e|E)[+-]digits]`，不是数值时返回 `None`
+pub(super) fn parse(input: &str) -> Option<Number> {
+    let bytes = input.as_bytes();
+    let mut pos = 0;
+    let negative = match bytes.first()? {
+        b'-' => {
+            pos += 1;
+            true
+        }
+        b'+' => {
+            pos += 1;
+            false
+        }
+        _ => false,
+    };
+    let digits = |pos: &mut usize| {
+        let start = *pos;
+        while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+            *pos += 1;
+        }
+        &input[start..*pos]
+    };
+
+    let int = digits(&mut pos);
+    let frac = if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        digits(&mut pos)
+    } else {
+        ""
+    };
+    if int.is_empty() && frac.is_empty() {
+        return None;
+    }
+    let exponent = if matches!(bytes.get(pos), Some(b'e' | b'E')) {
+        pos += 1;
+        let sign = match bytes.get(pos) {
+            Some(b'-') => {
+                pos += 1;
+                -1
+            }
+            Some(b'+') => {
+                pos += 1;
+                1
+            }
+            _ => 1,
+        };
+        let exp = digits(&mut pos);
+        if exp.is_empty() {
+            return None;
+        }
+        // 过大的指数按上限处理，之后会报告超出范围
+        sign * exp.parse::<i32>().unwrap_or(i32::MAX).min(MAX_EXPONENT)
+    } else {
+        0
+    };
+    if pos != bytes.len() {
+        return None;
+    }
+
+    // 按指数移动小数点
+    let mantissa = format!("{int}{frac}");
+    let point = int.len() as i32 + exponent;
+    let (int, frac) = if point <= 0 {
+        (String::new(), "0".repeat(-point as usize) + &mantissa)
+    } else if point as usize >= mantissa.len() {
+        (
+            mantissa.clone() + &"0".repeat(point as usize - mantissa.len()),
+            String::new(),
+        )
+    } else {
+        let (int, frac) = mantissa.split_at(point as usize);
+        (int.to_owned(), frac.to_owned())
+    };
+    Some(Number {
+        negative,
+        int: int.trim_start_matches('0').to_owned(),
+        frac,
+        plain: !input.starts_with(['+', '-']) && !input.contains(['.', 'e', 'E']),
+    })
+}
+
+impl Number {
+    /// 整数部分的位数，不含前导零
+    pub(super) fn int_digits(&self) -> usize {
+        self.int.len()
+    }
+
+    pub(super) fn has_fraction(&self) -> bool {
+        !self.frac.is_empty()
+    }
+
+    /// 转为秒数。整数部分超过 `max_int_digits` 位时，多出的位数（最多 9 位）视为秒的
+    /// 小数部分，以兼容毫秒、微秒和纳秒时间戳。纳秒以下的位数被截断。
+    pub(super) fn to_delta(&self, max_int_digits: Option<usize>) -> Result<TimeDelta, Warning> {
+        let len = self.int.len();
+        let split = match max_int_digits {
+            Some(max) => len.min(max).max(len.saturating_sub(9)),
+            None => len,
+        };
+        let (secs, extra) = self.int.split_at(split);
+        let frac = format!("{extra}{}", self.frac);
+        let secs = if secs.is_empty() {
+            0
+        } else {
+            secs.parse::<i64>().map_err(|_| Warning::OutOfRange)?
+        };
+        let nanos = format!("{:0<9}", frac.get(..9).unwrap_or(&frac))
+            .parse::<i64>()
+            .unwrap_or_default();
+        let delta = TimeDelta::try_seconds(secs).ok_or(Warning::OutOfRange)?
+            + TimeDelta::nanoseconds(nanos);
+        Ok(if self.negative { -delta } else { delta })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn number(negative: bool, int: &str, frac: &str, plain: bool) -> Option<Number> {
+        Some(Number {
+            negative,
+            int: int.to_owned(),
+            frac: frac.to_owned(),
+            plain,
+        })
+    }
+
+    #[test]
+    fn tokenizes_numbers() {
+        assert_eq!(parse("1700000000"), number(false, "1700000000", "", true));
+        assert_eq!(parse("-0012.50"), number(true, "12", "50", false));
+        assert_eq!(parse("+.5"), number(false, "", "5", false));
+        assert_eq!(parse("1.7e9"), number(false, "1700000000", "", false));
+        assert_eq!(parse("17E-1"), number(false, "1", "7", false));
+        assert_eq!(parse("5e-3"), number(false, "", "005", false));
+    }
+
+    #[test]
+    fn rejects_non_numbers() {
+        for input in ["", "-", ".", "1e", "1.2.3", "12a", "e5", " 1"] {
+            assert_eq!(parse(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn extra_digits_become_fraction() {
+        let delta = |input| parse(input).unwrap().to_delta(Some(10));
+        assert_eq!(delta("1700000000"), Ok(TimeDelta::seconds(1700000000)));
+        assert_eq!(
+            delta("1700000000123"),
+            Ok(TimeDelta::seconds(1700000000) + TimeDelta::milliseconds(123))
+        );
+        assert_eq!(
+            delta("-1.5"),
+            Ok(-(TimeDelta::seconds(1) + TimeDelta::milliseconds(500)))
+        );
+        // 纳秒以下截断
+        assert_eq!(delta("0.0000000019"), Ok(TimeDelta::nanoseconds(1)));
+        assert_eq!(delta("1e64"), Err(Warning::OutOfRange));
+    }
+}
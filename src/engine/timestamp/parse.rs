@@ -0,0 +1,163 @@
+//! 识别多种常见格式的时间字符串。
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, prelude::*};
+
+/// 解析出的时间
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsedTime {
+    /// 带有时区偏移，是确定的时刻
+    Absolute(DateTime<FixedOffset>),
+    /// 没有时区信息，需按所选时区解释
+    Local(NaiveDateTime),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Parsed {
+    pub time: ParsedTime,
+    /// 匹配到的格式名称
+    pub format: &'static str,
+}
+
+/// 带偏移的格式，`%#z` 同时接受 `Z`、`+08`、`+0800` 和 `+08:00`
+const ZONED_FORMATS: &[(&str, &str)] = &[
+    ("%Y-%m-%dT%H:%M:%S%.f%#z", "ISO 8601"),
+    ("%Y-%m-%d %H:%M:%S%.f%#z", "ISO 8601"),
+    ("%Y-%m-%d %H:%M:%S%.f %#z", "ISO 8601"),
+    ("%Y%m%dT%H%M%S%.f%#z", "ISO 8601 紧凑格式"),
+    ("%d/%b/%Y:%H:%M:%S %z", "Apache/Nginx 访问日志"),
+];
+
+/// 不带偏移的格式
+const LOCAL_FORMATS: &[(&str, &str)] = &[
+    ("%Y-%m-%d %H:%M:%S%.f", "%F %T"),
+    ("%Y-%m-%dT%H:%M:%S%.f", "ISO 8601（无时区）"),
+    ("%Y%m%dT%H%M%S%.f", "ISO 8601 紧凑格式（无时区）"),
+    ("%Y-%m-%d %H:%M:%S,%3f", "log4j/Python logging"),
+    ("%Y/%m/%d %H:%M:%S%.f", "%Y/%m/%d %T"),
+    ("%Y-%m-%d %H:%M", "%F %R"),
+    ("%Y-%m-%dT%H:%M", "ISO 8601（无时区）"),
+    ("%a %b %e %H:%M:%S %Y", "ctime"),
+];
+
+/// 依次尝试已知格式，返回第一个匹配的结果。
+pub fn parse(input: &str) -> Option<Parsed> {
+    let input = input.trim();
+    // 纯数字交给时间戳处理
+    if input.is_empty() || input.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let absolute = |time, format| {
+        Some(Parsed {
+            time: ParsedTime::Absolute(time),
+            format,
+        })
+    };
+    let local = |time, format| {
+        Some(Parsed {
+            time: ParsedTime::Local(time),
+            format,
+        })
+    };
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return absolute(dt, "RFC 3339");
+    }
+    for (fmt, name) in ZONED_FORMATS {
+        if let Ok(dt) = DateTime::parse_from_str(input, fmt) {
+            return absolute(dt, name);
+        }
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(input) {
+        return absolute(dt, "RFC 2822");
+    }
+    for (fmt, name) in LOCAL_FORMATS {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(input, fmt) {
+            return local(dt, name);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return local(date.and_time(NaiveTime::MIN), "%F");
+    }
+    // syslog 不含年份，按今年处理
+    let with_year = format!("{} {input}", Local::now().year());
+    if let Ok(dt) = NaiveDateTime::parse_from_str(&with_year, "%Y %b %e %H:%M:%S") {
+        return local(dt, "syslog（按今年）");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(input: &str) -> (NaiveDateTime, &'static str) {
+        match parse(input) {
+            Some(Parsed {
+                time: ParsedTime::Local(dt),
+                format,
+            }) => (dt, format),
+            other => panic!("{input}: {other:?}"),
+        }
+    }
+
+    fn absolute(input: &str) -> (i64, &'static str) {
+        match parse(input) {
+            Some(Parsed {
+                time: ParsedTime::Absolute(dt),
+                format,
+            }) => (dt.timestamp(), format),
+            other => panic!("{input}: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn zoned_formats() {
+        assert_eq!(absolute("2023-11-14T22:13:20Z"), (1700000000, "RFC 3339"));
+        assert_eq!(
+            absolute("2023-11-15 06:13:20+0800"),
+            (1700000000, "ISO 8601")
+        );
+        assert_eq!(
+            absolute("20231114T221320Z"),
+            (1700000000, "ISO 8601 紧凑格式")
+        );
+        assert_eq!(
+            absolute("14/Nov/2023:22:13:20 +0000"),
+            (1700000000, "Apache/Nginx 访问日志")
+        );
+        assert_eq!(
+            absolute("Tue, 14 Nov 2023 22:13:20 GMT"),
+            (1700000000, "RFC 2822")
+        );
+    }
+
+    #[test]
+    fn local_formats() {
+        let expected = NaiveDate::from_ymd_opt(2023, 11, 14)
+            .unwrap()
+            .and_hms_milli_opt(22, 13, 20, 123)
+            .unwrap();
+        assert_eq!(local("2023-11-14 22:13:20.123"), (expected, "%F %T"));
+        assert_eq!(
+            local("2023-11-14 22:13:20,123"),
+            (expected, "log4j/Python logging")
+        );
+        assert_eq!(local("2023/11/14 22:13:20.123").0, expected);
+        assert_eq!(
+            local("Tue Nov 14 22:13:20 2023").0,
+            expected.with_nanosecond(0).unwrap()
+        );
+        assert_eq!(
+            local("2023-11-14").0,
+            expected.date().and_time(NaiveTime::MIN)
+        );
+    }
+
+    #[test]
+    fn digits_and_garbage_are_not_datetimes() {
+        assert_eq!(parse("1700000000"), None);
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("yesterday"), None);
+    }
+}
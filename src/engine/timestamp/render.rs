@@ -0,0 +1,193 @@
+//! 同一时刻的多种表示形式。
+
+use std::fmt::Write;
+
+use chrono::{SecondsFormat, format::StrftimeItems, prelude::*};
+
+use super::{ALL_EPOCHS, Epoch, TimeUnit, Zone, format_in, timestamp_in};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rendering {
+    pub label: &'static str,
+    pub value: String,
+}
+
+/// 列出 `instant` 的常用表示形式，时间字符串使用 `zone` 时区，相对时间以 `now` 为准。
+pub fn renderings(instant: DateTime<Utc>, zone: Zone, now: DateTime<Utc>) -> Vec<Rendering> {
+    let dt = zone.from_utc(&instant.naive_utc());
+    let mut rows = vec![
+        ("ISO 8601", dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)),
+        ("RFC 2822", dt.to_rfc2822()),
+        ("UTC", instant.format("%F %T%.f UTC").to_string()),
+        (
+            "本地时间",
+            instant.with_timezone(&Local).format("%F %T%.f").to_string(),
+        ),
+        (
+            "星期",
+            format!("{} ({})", weekday_name(dt.weekday()), dt.format("%A")),
+        ),
+        ("ISO 周", dt.format("%G-W%V-%u").to_string()),
+        ("相对时间", relative(instant, now)),
+    ];
+    for (label, unit) in [
+        ("秒", TimeUnit::Sec),
+        ("毫秒", TimeUnit::Milli),
+        ("微秒", TimeUnit::Micro),
+        ("纳秒", TimeUnit::Nano),
+    ] {
+        let value =
+            timestamp_in(instant, unit).map_or_else(|| "超出范围".to_owned(), |ts| ts.to_string());
+        rows.push((label, value));
+    }
+    for epoch in &ALL_EPOCHS[1..] {
+        rows.push((epoch.label(), epoch.from_instant(instant, zone)));
+    }
+    let (week, seconds) = Epoch::gps_week(instant);
+    rows.push(("GPS 周/周内秒", format!("{week} / {seconds:.3}")));
+    rows.into_iter()
+        .map(|(label, value)| Rendering { label, value })
+        .collect()
+}
+
+/// 以自定义的 strftime 格式输出，格式无效时返回错误
+pub fn format_custom(instant: DateTime<Utc>, zone: Zone, pattern: &str) -> Result<String, String> {
+    let items = StrftimeItems::new(pattern)
+        .parse()
+        .map_err(|_| "无效的格式".to_owned())?;
+    let dt = zone.from_utc(&instant.naive_utc());
+    let mut output = String::new();
+    write!(output, "{}", dt.format_with_items(items.iter()))
+        .map_err(|_| "该格式无法输出此时间".to_owned())?;
+    Ok(output)
+}
+
+/// 如“3 小时前”“2 天后”，取最大的非零单位
+pub fn relative(instant: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta = now.signed_duration_since(instant);
+    let secs = delta.num_seconds().unsigned_abs();
+    if secs == 0 {
+        return "刚刚".to_owned();
+    }
+    let (value, unit) = match secs {
+        ..60 => (secs, "秒"),
+        60..3600 => (secs / 60, "分钟"),
+        3600..86_400 => (secs / 3600, "小时"),
+        86_400..2_592_000 => (secs / 86_400, "天"),
+        2_592_000..31_536_000 => (secs / 2_592_000, "个月"),
+        _ => (secs / 31_536_000, "年"),
+    };
+    let direction = if delta.num_seconds() > 0 {
+        "前"
+    } else {
+        "后"
+    };
+    format!("{value} {unit}{direction}")
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "星期一",
+        Weekday::Tue => "星期二",
+        Weekday::Wed => "星期三",
+        Weekday::Thu => "星期四",
+        Weekday::Fri => "星期五",
+        Weekday::Sat => "星期六",
+        Weekday::Sun => "星期日",
+    }
+}
+
+/// 实时时钟的各行：四种单位的时间戳、UTC、本地时间，以及 `zone` 时区（如不同）
+pub fn clock(now: DateTime<Utc>, zone: Zone) -> Vec<Rendering> {
+    let mut rows = vec![];
+    for (label, unit) in [
+        ("秒", TimeUnit::Sec),
+        ("毫秒", TimeUnit::Milli),
+        ("微秒", TimeUnit::Micro),
+        ("纳秒", TimeUnit::Nano),
+    ] {
+        let value = timestamp_in(now, unit).unwrap_or_default().to_string();
+        rows.push(Rendering { label, value });
+    }
+    rows.push(Rendering {
+        label: "UTC",
+        value: format_in(now, Zone::Utc, TimeUnit::Milli),
+    });
+    rows.push(Rendering {
+        label: "本地时间",
+        value: format_in(now, Zone::Local, TimeUnit::Milli),
+    });
+    if !matches!(zone, Zone::Local | Zone::Utc) {
+        rows.push(Rendering {
+            label: "所选时区",
+            value: format_in(now, zone, TimeUnit::Milli),
+        });
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    fn instant() -> DateTime<Utc> {
+        DateTime::from_timestamp(1700000000, 123_000_000).unwrap()
+    }
+
+    #[test]
+    fn renders_all_rows() {
+        let rows = renderings(instant(), Zone::Utc, instant());
+        let value = |label| {
+            rows.iter()
+                .find(|row| row.label == label)
+                .map(|row| row.value.as_str())
+        };
+        assert_eq!(value("ISO 8601"), Some("2023-11-14T22:13:20.123Z"));
+        assert_eq!(value("UTC"), Some("2023-11-14 22:13:20.123 UTC"));
+        assert_eq!(value("星期"), Some("星期二 (Tuesday)"));
+        assert_eq!(value("ISO 周"), Some("2023-W46-2"));
+        assert_eq!(value("相对时间"), Some("刚刚"));
+        assert_eq!(value("毫秒"), Some("1700000000123"));
+    }
+
+    #[test]
+    fn custom_format() {
+        let shanghai = "Asia/Shanghai".parse().unwrap();
+        assert_eq!(
+            format_custom(instant(), shanghai, "%Y年%m月%d日 %H时").unwrap(),
+            "2023年11月15日 06时"
+        );
+        assert!(format_custom(instant(), Zone::Utc, "%Q").is_err());
+    }
+
+    #[test]
+    fn relative_time() {
+        let now = instant();
+        assert_eq!(relative(now - TimeDelta::seconds(30), now), "30 秒前");
+        assert_eq!(relative(now + TimeDelta::hours(3), now), "3 小时后");
+        assert_eq!(relative(now - TimeDelta::days(400), now), "1 年前");
+    }
+
+    #[test]
+    fn clock_rows() {
+        let labels = |zone| {
+            clock(instant(), zone)
+                .into_iter()
+                .map(|row| row.label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            labels(Zone::Utc),
+            ["秒", "毫秒", "微秒", "纳秒", "UTC", "本地时间"]
+        );
+        let rows = clock(instant(), "+08:00".parse().unwrap());
+        assert_eq!(rows[1].value, "1700000000123");
+        let last = rows.last().unwrap();
+        assert_eq!(
+            (last.label, last.value.as_str()),
+            ("所选时区", "2023-11-15 06:13:20.123")
+        );
+    }
+}
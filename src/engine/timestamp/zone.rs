@@ -0,0 +1,194 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone, prelude::*};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+/// 转换时使用的时区
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Zone {
+    #[default]
+    Local,
+    Utc,
+    Fixed(FixedOffset),
+    /// IANA 时区，如 `Asia/Shanghai`
+    Named(Tz),
+}
+
+/// 时区选择器中列出的常用时区
+pub const COMMON_ZONES: &[Zone] = &[
+    Zone::Local,
+    Zone::Utc,
+    Zone::Named(Tz::Asia__Shanghai),
+    Zone::Named(Tz::Asia__Tokyo),
+    Zone::Named(Tz::Asia__Singapore),
+    Zone::Named(Tz::Europe__Berlin),
+    Zone::Named(Tz::Europe__London),
+    Zone::Named(Tz::America__New_York),
+    Zone::Named(Tz::America__Los_Angeles),
+];
+
+impl Zone {
+    /// 将 UTC 时间转换到该时区
+    pub fn from_utc(&self, utc: &NaiveDateTime) -> DateTime<FixedOffset> {
+        match self {
+            Zone::Local => Local.from_utc_datetime(utc).fixed_offset(),
+            Zone::Utc => Utc.from_utc_datetime(utc).fixed_offset(),
+            Zone::Fixed(offset) => offset.from_utc_datetime(utc),
+            Zone::Named(tz) => tz.from_utc_datetime(utc).fixed_offset(),
+        }
+    }
+
+    /// 将该时区的本地时间转换为带偏移的时间，夏令时切换时可能不存在或有歧义
+    pub fn from_local(&self, local: &NaiveDateTime) -> LocalResult<DateTime<FixedOffset>> {
+        match self {
+            Zone::Local => Local.from_local_datetime(local).map(|dt| dt.fixed_offset()),
+            Zone::Utc => Utc.from_local_datetime(local).map(|dt| dt.fixed_offset()),
+            Zone::Fixed(offset) => offset.from_local_datetime(local),
+            Zone::Named(tz) => tz.from_local_datetime(local).map(|dt| dt.fixed_offset()),
+        }
+    }
+
+    /// 用于界面展示的名称，包含当前的 UTC 偏移
+    pub fn label(&self) -> String {
+        let offset = self.from_utc(&Utc::now().naive_utc()).offset().fix();
+        match self {
+            Zone::Local => format!("本地 ({offset})"),
+            Zone::Utc => "UTC".to_owned(),
+            Zone::Fixed(_) => format!("UTC{offset}"),
+            Zone::Named(tz) => format!("{} ({offset})", tz.name()),
+        }
+    }
+}
+
+impl fmt::Display for Zone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Zone::Local => f.write_str("local"),
+            Zone::Utc => f.write_str("UTC"),
+            Zone::Fixed(offset) => write!(f, "{offset}"),
+            Zone::Named(tz) => f.write_str(tz.name()),
+        }
+    }
+}
+
+impl FromStr for Zone {
+    type Err = String;
+
+    /// 支持 `local`、`UTC`、`+08:00`/`-0530`/`UTC+8` 形式的固定偏移，以及 IANA 时区名
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s.to_ascii_lowercase().as_str() {
+            "" | "local" | "本地" => return Ok(Zone::Local),
+            "utc" | "z" | "gmt" => return Ok(Zone::Utc),
+            _ => {}
+        }
+        if let Some(offset) = parse_offset(s) {
+            return Ok(Zone::Fixed(offset));
+        }
+        s.parse::<Tz>()
+            .map(Zone::Named)
+            .map_err(|_| format!("未知的时区：{s}"))
+    }
+}
+
+impl TryFrom<String> for Zone {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Zone> for String {
+    fn from(zone: Zone) -> Self {
+        zone.to_string()
+    }
+}
+
+/// 解析 `+08:00`、`-0530`、`+8`、`UTC+8` 形式的偏移，`UTC`/`GMT` 前缀不区分大小写
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let s = match s.get(..3).map(str::to_ascii_lowercase).as_deref() {
+        Some("utc" | "gmt") => &s[3..],
+        _ => s,
+    };
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    let hours = hours.parse::<i32>().ok()?;
+    let minutes = minutes.parse::<i32>().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_zones() {
+        assert_eq!("".parse(), Ok(Zone::Local));
+        assert_eq!("utc".parse(), Ok(Zone::Utc));
+        assert_eq!("Z".parse(), Ok(Zone::Utc));
+        assert_eq!("Asia/Shanghai".parse(), Ok(Zone::Named(Tz::Asia__Shanghai)));
+        assert!("Mars/Base".parse::<Zone>().is_err());
+    }
+
+    #[test]
+    fn parses_offsets() {
+        let east = |secs| Ok(Zone::Fixed(FixedOffset::east_opt(secs).unwrap()));
+        assert_eq!("+08:00".parse(), east(8 * 3600));
+        assert_eq!("-0530".parse(), east(-(5 * 3600 + 30 * 60)));
+        assert_eq!("UTC+8".parse(), east(8 * 3600));
+        assert_eq!("GMT-5".parse(), east(-5 * 3600));
+        assert_eq!("utc+8".parse(), east(8 * 3600));
+        assert_eq!("gmt-05:30".parse(), east(-(5 * 3600 + 30 * 60)));
+        assert!("+24".parse::<Zone>().is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for zone in COMMON_ZONES {
+            assert_eq!(zone.to_string().parse(), Ok(*zone));
+        }
+        let fixed = "+05:30".parse::<Zone>().unwrap();
+        assert_eq!(fixed.to_string().parse(), Ok(fixed));
+    }
+
+    #[test]
+    fn named_zones_follow_dst() {
+        let new_york = Zone::Named(Tz::America__New_York);
+        let winter = NaiveDate::from_ymd_opt(2024, 1, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let summer = NaiveDate::from_ymd_opt(2024, 7, 15)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        assert_eq!(
+            new_york.from_utc(&winter).offset().local_minus_utc(),
+            -5 * 3600
+        );
+        assert_eq!(
+            new_york.from_utc(&summer).offset().local_minus_utc(),
+            -4 * 3600
+        );
+        // 夏令时开始时跳过的本地时间不存在
+        let skipped = NaiveDate::from_ymd_opt(2024, 3, 10)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        assert_eq!(new_york.from_local(&skipped), LocalResult::None);
+    }
+}
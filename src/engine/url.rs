@@ -0,0 +1,44 @@
+//! URL 编解码。
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Conversion {
+    Encode,
+    #[default]
+    Decode,
+}
+
+pub fn encode(input: &str) -> String {
+    urlencoding::encode(input).into_owned()
+}
+
+pub fn decode(input: &str) -> Result<String, String> {
+    urlencoding::decode(input)
+        .map(|decoded| decoded.into_owned())
+        .map_err(|e| e.to_string())
+}
+
+pub fn convert(input: &str, conversion: Conversion) -> Result<String, String> {
+    match conversion {
+        Conversion::Encode => Ok(encode(input)),
+        Conversion::Decode => decode(input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode() {
+        assert_eq!(encode("a b&c=中"), "a%20b%26c%3D%E4%B8%AD");
+        assert_eq!(decode("a%20b%26c%3D%E4%B8%AD").unwrap(), "a b&c=中");
+        assert_eq!(convert("a b", Conversion::Encode).unwrap(), "a%20b");
+    }
+
+    #[test]
+    fn decode_rejects_invalid_utf8() {
+        assert!(decode("%FF").is_err());
+    }
+}
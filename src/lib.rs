@@ -3,7 +3,15 @@
 #![warn(clippy::all)]
 
 mod app;
+mod clipboard;
+mod deep_link;
+pub mod engine;
+mod palette;
+mod persistence;
+mod settings;
 mod style;
 mod tool_card;
 mod tools;
 pub use app::App;
+pub use deep_link::DeepLink;
+pub use tools::{Availability, Category, ToolAction, ToolInfo, ToolItem, ToolRegistry};
@@ -2,9 +2,20 @@
 #![cfg_attr(not(debug_assertions), deny(warnings))] // Forbid warnings in release builds
 #![warn(clippy::all)]
 
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
-fn main() {
+fn main() -> std::process::ExitCode {
+    // 以子命令开头时作为命令行工具运行
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if let Some(code) = cli::run(&args) {
+        return code;
+    }
+    // 其余参数视为跳转链接，如 `--tool timestamp --input 1700000000123`
+    let link = handy::DeepLink::from_args(&args);
+
     let native_options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
             .with_inner_size([1200.0, 600.0])
@@ -18,9 +29,16 @@ fn main() {
     eframe::run_native(
         "Handy Tools",
         native_options,
-        Box::new(|cc| Ok(Box::new(handy::App::new(cc)))),
+        Box::new(|cc| {
+            let mut app = handy::App::new(cc);
+            if let Some(link) = link {
+                app.open_link(&link, &cc.egui_ctx);
+            }
+            Ok(Box::new(app))
+        }),
     )
     .unwrap();
+    std::process::ExitCode::SUCCESS
 }
 
 #[cfg(target_arch = "wasm32")]
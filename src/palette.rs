@@ -0,0 +1,248 @@
+use eframe::egui::{
+    self, Id, Key, KeyboardShortcut, Modifiers, RichText, ScrollArea, TextEdit, Ui,
+};
+
+use crate::{app::ToolSlot, style};
+
+pub(crate) const SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::K);
+const MAX_ENTRIES: usize = 12;
+
+/// 命令面板中的一项：切换到工具，或切换后执行工具的操作
+struct Entry {
+    tool: usize,
+    action: Option<&'static str>,
+    title: String,
+    detail: String,
+    score: i32,
+}
+
+/// Ctrl+K（macOS 上为 Cmd+K）打开的命令面板
+#[derive(Default)]
+pub(crate) struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    /// 处理快捷键并绘制面板。
+    ///
+    /// `visible` 为边栏中可见工具的下标，返回选中的工具下标及要执行的操作 id。
+    pub(crate) fn show(
+        &mut self,
+        ctx: &egui::Context,
+        tools: &[ToolSlot],
+        visible: &[usize],
+    ) -> Option<(usize, Option<&'static str>)> {
+        if ctx.input_mut(|i| i.consume_shortcut(&SHORTCUT)) {
+            self.open = !self.open;
+            self.query.clear();
+            self.selected = 0;
+        }
+        if !self.open {
+            return None;
+        }
+
+        let entries = search(&self.query, tools, visible);
+        let (up, down, enter) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down {
+            self.selected += 1;
+        }
+        self.selected = self.selected.min(entries.len().saturating_sub(1));
+
+        let mut chosen = enter.then_some(self.selected);
+        let modal = egui::Modal::new(Id::new("command_palette")).show(ctx, |ui| {
+            ui.set_width(480.0);
+            let response = ui.add(
+                TextEdit::singleline(&mut self.query)
+                    .hint_text("搜索工具或操作…")
+                    .desired_width(f32::INFINITY),
+            );
+            response.request_focus();
+            if response.changed() {
+                self.selected = 0;
+            }
+            ui.separator();
+            if entries.is_empty() {
+                ui.weak("没有匹配的工具或操作");
+            }
+            ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                for (i, entry) in entries.iter().enumerate() {
+                    if entry_row(ui, entry, i == self.selected).clicked() {
+                        chosen = Some(i);
+                    }
+                }
+            });
+        });
+
+        let chosen = chosen.and_then(|i| entries.get(i));
+        if chosen.is_some() || modal.should_close() {
+            self.open = false;
+        }
+        chosen.map(|entry| (entry.tool, entry.action))
+    }
+}
+
+fn entry_row(ui: &mut Ui, entry: &Entry, selected: bool) -> egui::Response {
+    let dark_mode = ui.visuals().dark_mode;
+    let response = ui.selectable_label(selected, RichText::new(&entry.title).strong());
+    if selected {
+        response.scroll_to_me(None);
+    }
+    ui.label(
+        RichText::new(&entry.detail)
+            .small()
+            .color(style::prompt_color(dark_mode)),
+    );
+    response
+}
+
+/// 按匹配度从高到低列出工具及其操作
+fn search(query: &str, tools: &[ToolSlot], visible: &[usize]) -> Vec<Entry> {
+    let mut entries = vec![];
+    for &i in visible {
+        let slot = &tools[i];
+        let name = slot.tool.name();
+        let description = slot.tool.description();
+        // 名称的匹配优先于描述
+        let score = [
+            fuzzy_score(query, name).map(|s| s * 2),
+            fuzzy_score(query, slot.info.id),
+            fuzzy_score(query, description),
+        ]
+        .into_iter()
+        .flatten()
+        .max();
+        if let Some(score) = score {
+            entries.push(Entry {
+                tool: i,
+                action: None,
+                title: format!("{} {}", slot.info.icon, name),
+                detail: description.to_owned(),
+                score,
+            });
+        }
+        for action in slot.tool.actions() {
+            let title = format!("{name}: {}", action.label);
+            if let Some(score) = fuzzy_score(query, &title) {
+                entries.push(Entry {
+                    tool: i,
+                    action: Some(action.id),
+                    title: format!("{} {title}", slot.info.icon),
+                    detail: format!("切换到{name}并执行"),
+                    // 没有输入时，工具排在操作之前
+                    score: score - 1,
+                });
+            }
+        }
+    }
+    // 分数相同时保持边栏顺序
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+    entries.truncate(MAX_ENTRIES);
+    entries
+}
+
+/// 子序列模糊匹配，不区分大小写。
+///
+/// `query` 的每个字符都需要按顺序出现在 `text` 中，否则返回 `None`。
+/// 连续匹配、匹配单词开头和匹配文本开头会得到更高的分数。
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut q = 0;
+    let mut last_match: Option<usize> = None;
+    let mut prev = None;
+    for (i, c) in text.chars().flat_map(char::to_lowercase).enumerate() {
+        if q < query.len() && c == query[q] {
+            score += 1;
+            if i == 0 {
+                score += 8;
+            }
+            match last_match {
+                Some(last) if last + 1 == i => score += 5,
+                Some(last) => score -= ((i - last - 1) as i32).min(3),
+                None => {}
+            }
+            if prev.is_some_and(|p: char| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            last_match = Some(i);
+            q += 1;
+        }
+        prev = Some(c);
+    }
+    (q == query.len()).then_some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::ToolRegistry;
+
+    fn slots() -> Vec<ToolSlot> {
+        ToolRegistry::builtin()
+            .iter()
+            .enumerate()
+            .map(|(default_order, info)| ToolSlot {
+                info: info.clone(),
+                tool: (info.create)(),
+                hidden: false,
+                default_order,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fuzzy_scores() {
+        let prefix = fuzzy_score("js", "json").unwrap();
+        let scattered = fuzzy_score("jn", "json").unwrap();
+        assert!(prefix > scattered);
+        assert!(fuzzy_score("on", "json").unwrap() < prefix);
+        assert_eq!(fuzzy_score("JSON", "json"), fuzzy_score("json", "JSON"));
+        assert_eq!(fuzzy_score("u r l", "url"), fuzzy_score("url", "url"));
+        assert_eq!(fuzzy_score("xyz", "json"), None);
+        assert_eq!(fuzzy_score("nj", "json"), None);
+        assert_eq!(fuzzy_score("", "json"), Some(0));
+    }
+
+    #[test]
+    fn searches_visible_tools() {
+        let tools = slots();
+        let all = (0..tools.len()).collect::<Vec<_>>();
+        let url = tools.iter().position(|slot| slot.info.id == "url").unwrap();
+
+        let entries = search("url", &tools, &all);
+        assert_eq!((entries[0].tool, entries[0].action), (url, None));
+        assert!(
+            entries
+                .iter()
+                .any(|entry| entry.tool == url && entry.action == Some("decode"))
+        );
+
+        let visible = all
+            .iter()
+            .copied()
+            .filter(|&i| i != url)
+            .collect::<Vec<_>>();
+        let entries = search("url", &tools, &visible);
+        assert!(entries.iter().all(|entry| entry.tool != url));
+        assert!(search("zzzz", &tools, &all).is_empty());
+    }
+}
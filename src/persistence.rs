@@ -0,0 +1,154 @@
+//! 应用状态的保存与恢复。
+//!
+//! 状态以 JSON 字符串形式写入 eframe 的存储（原生为本地文件，网页为 localStorage），
+//! 并带有版本号，升级后旧版本的状态经过迁移仍可读取。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use eframe::Storage;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+
+const STORAGE_KEY: &str = "handy_state";
+
+/// 当前的状态格式版本，修改格式时递增并在 [`migrate`] 中补充迁移步骤
+pub(crate) const STATE_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub(crate) struct SavedState {
+    pub(crate) version: u32,
+    /// 当前选中工具的 id
+    pub(crate) active_tool: Option<String>,
+    /// 各工具自行保存的状态，以工具 id 为键
+    pub(crate) tools: BTreeMap<String, Value>,
+    /// 边栏中工具的顺序，未列出的工具排在最后
+    pub(crate) tool_order: Vec<String>,
+    pub(crate) hidden_tools: BTreeSet<String>,
+    /// 未设置时仅在调试构建中显示实验性工具
+    pub(crate) show_experimental: Option<bool>,
+}
+
+impl Default for SavedState {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            active_tool: None,
+            tools: BTreeMap::new(),
+            tool_order: Vec::new(),
+            hidden_tools: BTreeSet::new(),
+            show_experimental: None,
+        }
+    }
+}
+
+impl SavedState {
+    pub(crate) fn load(storage: &dyn Storage) -> Option<Self> {
+        let text = storage.get_string(STORAGE_KEY)?;
+        let value = serde_json::from_str::<Value>(&text)
+            .inspect_err(|e| log::warn!("无法解析保存的状态：{e}"))
+            .ok()?;
+        migrate(value)
+    }
+
+    pub(crate) fn save(&self, storage: &mut dyn Storage) {
+        match serde_json::to_string(self) {
+            Ok(text) => storage.set_string(STORAGE_KEY, text),
+            Err(e) => log::warn!("无法保存状态：{e}"),
+        }
+    }
+}
+
+/// 将旧版本的状态逐步升级到 [`STATE_VERSION`]。
+///
+/// 来自更新版本的状态无法可靠读取，直接丢弃。
+fn migrate(value: Value) -> Option<SavedState> {
+    let Value::Object(mut fields) = value else {
+        log::warn!("保存的状态不是对象，已忽略");
+        return None;
+    };
+    let version = fields.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > STATE_VERSION as u64 {
+        log::warn!("忽略来自更新版本的状态（版本 {version}）");
+        return None;
+    }
+    // 版本 0 为未带版本号的格式，各字段均可按默认值读取
+    Some(SavedState {
+        version: STATE_VERSION,
+        active_tool: field(&mut fields, "active_tool"),
+        tools: field(&mut fields, "tools"),
+        tool_order: field(&mut fields, "tool_order"),
+        hidden_tools: field(&mut fields, "hidden_tools"),
+        show_experimental: field(&mut fields, "show_experimental"),
+    })
+}
+
+/// 逐个字段读取，某个字段损坏时只丢弃该字段
+fn field<T: DeserializeOwned + Default>(fields: &mut Map<String, Value>, name: &str) -> T {
+    let Some(value) = fields.remove(name) else {
+        return T::default();
+    };
+    serde_json::from_value(value).unwrap_or_else(|e| {
+        log::warn!("忽略保存的状态中无法读取的字段 {name}：{e}");
+        T::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn migrates_unversioned_state() {
+        let state = migrate(json!({
+            "active_tool": "json",
+            "tools": {"json": {"input": "{}"}},
+            "hidden_tools": ["url"],
+        }))
+        .unwrap();
+        assert_eq!(state.version, STATE_VERSION);
+        assert_eq!(state.active_tool.as_deref(), Some("json"));
+        assert_eq!(state.tools["json"], json!({"input": "{}"}));
+        assert!(state.hidden_tools.contains("url"));
+        assert!(state.tool_order.is_empty());
+        assert_eq!(state.show_experimental, None);
+    }
+
+    #[test]
+    fn reads_current_version() {
+        let saved = SavedState {
+            active_tool: Some("timestamp".to_owned()),
+            tool_order: vec!["timestamp".to_owned(), "json".to_owned()],
+            show_experimental: Some(true),
+            ..Default::default()
+        };
+        let state = migrate(serde_json::to_value(&saved).unwrap()).unwrap();
+        assert_eq!(state.active_tool, saved.active_tool);
+        assert_eq!(state.tool_order, saved.tool_order);
+        assert_eq!(state.show_experimental, Some(true));
+    }
+
+    #[test]
+    fn drops_newer_version() {
+        let newer = json!({"version": STATE_VERSION + 1, "active_tool": "json"});
+        assert!(migrate(newer).is_none());
+        assert!(migrate(json!([1, 2])).is_none());
+    }
+
+    #[test]
+    fn skips_unknown_and_broken_fields() {
+        let state = migrate(json!({
+            "version": STATE_VERSION,
+            "active_tool": "json",
+            "tool_order": 5,
+            "hidden_tools": ["url"],
+            "unknown": true,
+        }))
+        .unwrap();
+        assert_eq!(state.active_tool.as_deref(), Some("json"));
+        assert!(state.tool_order.is_empty());
+        assert!(state.hidden_tools.contains("url"));
+        assert!(state.tools.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use eframe::egui::{Button, Grid, RichText, Ui};
+
+use crate::{app::ToolSlot, tools::Availability};
+
+/// 工具设置页面：显示/隐藏工具、调整边栏顺序。
+///
+/// 返回是否修改了“显示实验性工具”选项。
+pub(crate) fn show(ui: &mut Ui, tools: &mut [ToolSlot], show_experimental: &mut bool) -> bool {
+    ui.heading("工具设置");
+    let experimental_changed = ui.checkbox(show_experimental, "显示实验性工具").changed();
+    ui.separator();
+
+    let mut move_up = None;
+    let mut move_down = None;
+    Grid::new("tool_settings")
+        .num_columns(5)
+        .spacing((16.0, 8.0))
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("显示");
+            ui.label("工具");
+            ui.label("分组");
+            ui.label("");
+            ui.label("顺序");
+            ui.end_row();
+
+            let count = tools.len();
+            for (i, slot) in tools.iter_mut().enumerate() {
+                let mut visible = !slot.hidden;
+                if ui.checkbox(&mut visible, "").changed() {
+                    slot.hidden = !visible;
+                }
+                ui.label(format!("{} {}", slot.info.icon, slot.tool.name()));
+                ui.label(slot.info.category.label());
+                let availability = slot.info.availability;
+                let tag = RichText::new(availability.label()).small();
+                if availability == Availability::Experimental && !*show_experimental {
+                    ui.label(tag.weak());
+                } else {
+                    ui.label(tag);
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(i > 0, Button::new("⏶")).clicked() {
+                        move_up = Some(i);
+                    }
+                    if ui.add_enabled(i + 1 < count, Button::new("⏷")).clicked() {
+                        move_down = Some(i);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    if let Some(i) = move_up {
+        tools.swap(i, i - 1);
+    }
+    if let Some(i) = move_down {
+        tools.swap(i, i + 1);
+    }
+
+    ui.add_space(8.0);
+    if ui.button("恢复默认").clicked() {
+        tools.sort_by_key(|slot| slot.default_order);
+        for slot in tools.iter_mut() {
+            slot.hidden = false;
+        }
+    }
+    experimental_changed
+}
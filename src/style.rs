@@ -48,18 +48,34 @@ pub(crate) fn log_message_color(dark_mode: bool) -> Color32 {
     }
 }
 
-pub(crate) fn log_error_color(dark_mode: bool) -> Color32 {
+pub(crate) fn log_source_color(dark_mode: bool) -> Color32 {
     if dark_mode {
-        Color32::from_hex("#ff6961").unwrap()
+        Color32::from_hex("#a3b18a").unwrap()
     } else {
-        Color32::from_hex("#ff0000").unwrap()
+        Color32::from_hex("#588157").unwrap()
     }
 }
 
-pub(crate) fn log_source_color(dark_mode: bool) -> Color32 {
+pub(crate) fn diff_added_color(dark_mode: bool) -> Color32 {
     if dark_mode {
-        Color32::from_hex("#a3b18a").unwrap()
+        Color32::from_hex("#8fd694").unwrap()
     } else {
-        Color32::from_hex("#588157").unwrap()
+        Color32::from_hex("#2d6a4f").unwrap()
+    }
+}
+
+pub(crate) fn diff_removed_color(dark_mode: bool) -> Color32 {
+    if dark_mode {
+        Color32::from_hex("#ff8a8a").unwrap()
+    } else {
+        Color32::from_hex("#c1121f").unwrap()
+    }
+}
+
+pub(crate) fn diff_changed_color(dark_mode: bool) -> Color32 {
+    if dark_mode {
+        Color32::from_hex("#ffd166").unwrap()
+    } else {
+        Color32::from_hex("#b5651d").unwrap()
     }
 }
@@ -1,13 +1,15 @@
 use eframe::egui::{Color32, RichText, Ui, widgets::Label};
 
 pub struct ToolCard<'a> {
+    icon: &'a str,
     caption: &'a str,
     description: &'a str,
 }
 
 impl<'a> ToolCard<'a> {
-    pub fn new(caption: &'a str, description: &'a str) -> Self {
+    pub fn new(icon: &'a str, caption: &'a str, description: &'a str) -> Self {
         ToolCard {
+            icon,
             caption,
             description,
         }
@@ -23,7 +25,9 @@ impl<'a> ToolCard<'a> {
         ui.vertical(|ui| {
             ui.set_min_height(60.0);
             ui.add(Label::new(
-                RichText::new(self.caption).heading().color(color),
+                RichText::new(format!("{} {}", self.icon, self.caption))
+                    .heading()
+                    .color(color),
             ));
             ui.add(Label::new(RichText::new(self.description).color(color)));
         });
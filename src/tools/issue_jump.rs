@@ -4,8 +4,9 @@ use serde::{Deserialize, Serialize};
 
 use crate::style;
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
 pub enum IdType {
+    #[default]
     Event,
     Triage,
     Data,
@@ -45,6 +46,13 @@ pub struct IssueJump {
     inbox: UiInbox<Result<u64, String>>,
 }
 
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    id: String,
+    id_type: IdType,
+}
+
 impl super::ToolItem for IssueJump {
     fn name(&self) -> &str {
         "Issue 快速跳转"
@@ -106,10 +114,7 @@ impl super::ToolItem for IssueJump {
                                 "https://aip.nioint.com/#/issue-scenario/issue/{}/info",
                                 result
                             );
-                            let open_url = OpenUrl {
-                                url: url,
-                                new_tab: true,
-                            };
+                            let open_url = OpenUrl { url, new_tab: true };
                             ui.ctx().open_url(open_url);
                         }
                         Err(err) => {
@@ -144,6 +149,29 @@ impl super::ToolItem for IssueJump {
             self.warning = String::new();
         }
     }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            id: self.id.clone(),
+            id_type: self.id_type,
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.id = state.id;
+        self.id_type = state.id_type;
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.id = input.trim().to_owned();
+        self.warning.clear();
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
 }
 
 impl Default for IssueJump {
@@ -193,7 +221,7 @@ impl Query {
                 };
                 let response = serde_json::from_slice::<ListResponse>(&res.bytes)
                     .map_err(|e| e.to_string())?;
-                if response.data.list.len() == 0 {
+                if response.data.list.is_empty() {
                     return Err("未找到对应 issue".to_string());
                 }
                 if response.code != 200 {
@@ -2,43 +2,100 @@ use std::f32;
 #[cfg(not(target_arch = "wasm32"))]
 use std::time::{Duration, Instant};
 
+use std::ops::Range;
+
 use eframe::egui::{
-    Align, Button, CursorIcon, Frame, Layout, RichText, ScrollArea, TextEdit, TextStyle, Ui,
-    UiKind, widgets::Label,
+    Align, Button, Color32, ComboBox, Context, CursorIcon, DragValue, Frame, Layout, RichText,
+    ScrollArea, Sense, Stroke, StrokeKind, TextBuffer, TextEdit, TextStyle, Ui, UiKind,
+    text::{CCursor, CCursorRange, LayoutJob, TextFormat},
+    widgets::Label,
 };
-use egui_json_tree::{DefaultExpand, JsonTree, JsonTreeStyle, render::DefaultRender};
-
-use crate::style;
+use egui_json_tree::{
+    DefaultExpand, JsonTree, JsonTreeStyle,
+    render::{DefaultRender, RenderContext},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(PartialEq, Eq)]
-pub enum Formatter {
-    None,
-    Pretty,
-    Minimize,
-}
+use super::ToolAction;
+use crate::{
+    engine::json::{
+        self, BinaryEncoding, Conversion, DataFormat, Formatter, InputMode, Language, SyntaxError,
+    },
+    style,
+};
+use diff::{DiffView, SavedDiff};
+use schema::{SavedSchema, SchemaView};
+use tree_edit::TreeEditor;
 
-#[derive(PartialEq, Eq)]
-pub enum Conversion {
-    None,
-    Serialize,
-    Deserialize,
-}
+mod diff;
+mod schema;
+mod tree_edit;
 
+#[derive(Default)]
 pub struct JsonConverter {
     input: String,
     converted: String,
-    format: Formatter,
-    conversion: Conversion,
+    options: json::Options,
     warning: String,
+    /// 解析错误的位置和修改建议
+    error: Option<SyntaxError>,
+    hint: Option<&'static str>,
+    /// 下一帧把输入框的光标移动到这个字符下标
+    jump_to: Option<usize>,
     use_json_tree: bool,
+    /// 处理结果解析出的 JSON，用于树形视图和查询
+    parsed: Option<Value>,
+    /// JSONPath 或 jq 查询
+    query: String,
+    /// 查询结果：多个结果合并为数组，以及结果个数
+    queried: Option<Result<(Value, usize), String>>,
     search_input: String,
+    /// 对比模式
+    diff: bool,
+    diff_view: DiffView,
+    /// JSON Schema 校验，出错的节点在树形视图中标出
+    schema_view: SchemaView,
+    /// 树形视图中的编辑和撤销栈
+    tree_editor: TreeEditor,
     copied_prompt: &'static str,
+    /// 复制提示消失的时间
     #[cfg(not(target_arch = "wasm32"))]
-    prompt_vanish_at: Instant,
-    pythonic_style: bool,
+    prompt_vanish_at: Option<Instant>,
     //use_single_quotes: bool,
 }
 
+const ACTIONS: &[ToolAction] = &[
+    ToolAction::new("process", "处理"),
+    ToolAction::new("json", "按 JSON 处理"),
+    ToolAction::new("python", "按 Python dict 处理"),
+    ToolAction::new("json5", "按 JSON5 / JSONC 处理"),
+    ToolAction::new("ndjson", "按 NDJSON 处理"),
+    ToolAction::new("deserialize", "反序列化"),
+    ToolAction::new("to_python", "转为 Python 字面量"),
+    ToolAction::new("to_yaml", "转为 YAML"),
+    ToolAction::new("rust_types", "生成 Rust 类型"),
+    ToolAction::new("typescript_types", "生成 TypeScript 类型"),
+    ToolAction::new("python_types", "生成 Python dataclass"),
+    ToolAction::new("pretty", "展开"),
+    ToolAction::new("minimize", "压缩"),
+    ToolAction::new("canonical", "输出规范化 JSON (JCS)"),
+    ToolAction::new("copy", "复制结果"),
+    ToolAction::new("diff", "对比两个 JSON"),
+    ToolAction::new("infer_schema", "推断 JSON Schema"),
+];
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    input: String,
+    options: json::Options,
+    query: String,
+    diff: bool,
+    diff_state: SavedDiff,
+    schema: SavedSchema,
+}
+
 impl super::ToolItem for JsonConverter {
     fn name(&self) -> &str {
         "JSON 转换"
@@ -57,8 +114,32 @@ impl super::ToolItem for JsonConverter {
         let line_height = ui.fonts(|fonts| fonts.row_height(&font_id));
         let input_rows = ((desired_height - label_height) / line_height).floor() as usize;
         #[cfg(not(target_arch = "wasm32"))]
-        if self.copied_prompt != "" && Instant::now() > self.prompt_vanish_at {
+        if self.prompt_vanish_at.is_some_and(|at| Instant::now() > at) {
             self.copied_prompt = "";
+            self.prompt_vanish_at = None;
+        }
+
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.diff, false, "转换");
+            ui.selectable_value(&mut self.diff, true, "对比");
+            if self.diff {
+                ui.add_space(16.0);
+                if ui
+                    .checkbox(&mut self.options.pythonic_style, "Python dict 风格")
+                    .changed()
+                {
+                    self.diff_view.compare(&self.diff_options());
+                }
+            } else {
+                ui.add_space(16.0);
+                if self.formats_ui(ui) {
+                    self.process();
+                }
+            }
+        });
+        if self.diff {
+            self.diff_view.show(ui, &self.diff_options());
+            return;
         }
 
         ui.horizontal(|ui| {
@@ -69,33 +150,32 @@ impl super::ToolItem for JsonConverter {
                     ScrollArea::vertical()
                         .id_salt("input")
                         .auto_shrink([false; 2])
-                        .show(ui, |ui| {
-                            ui.add(
-                                TextEdit::multiline(&mut self.input)
-                                    .desired_width(f32::INFINITY)
-                                    .desired_rows(input_rows)
-                                    .code_editor(),
-                            );
-                        });
+                        .show(ui, |ui| self.input_ui(ui, input_rows));
                 });
                 col[1].vertical(|ui| {
                     ui.horizontal(|ui| {
                         ui.label("转换结果");
                         if ui.button("复制").clicked() {
-                            ui.ctx().copy_text(self.converted.clone());
-                            self.copied_prompt = "已复制";
-                            #[cfg(not(target_arch = "wasm32"))]
-                            {
-                                self.prompt_vanish_at = Instant::now() + Duration::from_secs(2);
-                            }
+                            self.copy_result(ui.ctx());
                         }
                         ui.add(Label::new(
                             RichText::new(self.copied_prompt)
                                 .color(style::prompt_color(ui.visuals().dark_mode)),
                         ));
                     });
+                    if self.parsed.is_some() {
+                        self.query_ui(ui);
+                    }
+                    if let Some(parsed) = &self.parsed {
+                        self.schema_view.show(ui, parsed);
+                    }
                     if self.use_json_tree {
                         // 使用 json viewer
+                        if let Some(parsed) = &mut self.parsed
+                            && self.tree_editor.toolbar_ui(ui, parsed)
+                        {
+                            self.after_edit();
+                        }
                         // 搜索框
                         let (text_edit_response, clear_button_response) = ui
                             .scope(|ui| {
@@ -118,30 +198,65 @@ impl super::ToolItem for JsonConverter {
                                 ui.add(|ui: &mut Ui| {
                                     ui.vertical(|ui| {
                                         // json view
-                                        let value = serde_json::from_str::<serde_json::Value>(
-                                            &self.converted,
-                                        )
-                                        .unwrap_or(serde_json::json!({}));
-                                        let response = JsonTree::new("json-tree", &value)
+                                        let empty = serde_json::json!({});
+                                        let value = match (&self.queried, &self.parsed) {
+                                            (Some(Ok((value, _))), _) => value,
+                                            (_, Some(value)) => value,
+                                            _ => &empty,
+                                        };
+                                        // 查询结果的路径与校验的文档不对应，不标出校验错误
+                                        let schema_view = match &self.queried {
+                                            Some(Ok(_)) => None,
+                                            _ => Some(&self.schema_view),
+                                        };
+                                        let warn_color = style::warn_color(ui.visuals().dark_mode);
+                                        // 只能编辑处理结果，不能编辑查询结果
+                                        let mut tree_editor =
+                                            schema_view.is_some().then_some(&mut self.tree_editor);
+                                        let response = JsonTree::new("json-tree", value)
                                             .style(JsonTreeStyle::new().abbreviate_root(true))
                                             .default_expand(DefaultExpand::SearchResultsOrAll(
                                                 &self.search_input,
                                             ))
                                             .on_render(|ui, context| {
-                                                context
-                                                    .render_default(ui)
+                                                let pointer =
+                                                    context.pointer().to_json_pointer_string();
+                                                let mut response = context.render_default(ui);
+                                                // 容器只在根节点标出括号，其余标出键
+                                                let messages = match (schema_view, &context) {
+                                                    (
+                                                        Some(schema_view),
+                                                        RenderContext::Property(_)
+                                                        | RenderContext::BaseValue(_),
+                                                    ) => schema_view.messages_at(&pointer),
+                                                    (Some(schema_view), _)
+                                                        if pointer.is_empty() =>
+                                                    {
+                                                        schema_view.messages_at(&pointer)
+                                                    }
+                                                    _ => vec![],
+                                                };
+                                                if !messages.is_empty() {
+                                                    ui.painter().rect_stroke(
+                                                        response.rect.expand(1.0),
+                                                        2.0,
+                                                        Stroke::new(1.0, warn_color),
+                                                        StrokeKind::Outside,
+                                                    );
+                                                    response =
+                                                        response.on_hover_text(messages.join("\n"));
+                                                }
+                                                response
                                                     .on_hover_cursor(CursorIcon::ContextMenu)
                                                     .context_menu(|ui| {
-                                                        let pointer = context
-                                                            .pointer()
-                                                            .to_json_pointer_string();
                                                         if !pointer.is_empty()
                                                             && ui.button("复制路径").clicked()
                                                         {
-                                                            ui.ctx().copy_text(format!(
-                                                                "${}",
-                                                                pointer.replace("/", ".")
-                                                            ));
+                                                            ui.ctx().copy_text(
+                                                                json::path_from_pointer(
+                                                                    value, &pointer,
+                                                                ),
+                                                            );
                                                             ui.close_kind(UiKind::Menu);
                                                         }
 
@@ -155,6 +270,16 @@ impl super::ToolItem for JsonConverter {
                                                             }
                                                             ui.close_kind(UiKind::Menu);
                                                         }
+
+                                                        if let Some(tree_editor) = &mut tree_editor
+                                                        {
+                                                            tree_editor.menu_ui(
+                                                                ui,
+                                                                value,
+                                                                &pointer,
+                                                                context.value(),
+                                                            );
+                                                        }
                                                     });
                                             })
                                             .show(ui);
@@ -171,6 +296,17 @@ impl super::ToolItem for JsonConverter {
                                     .response
                                 });
                             });
+                        if let Some(parsed) = &self.parsed {
+                            self.tree_editor.form_ui(ui.ctx(), parsed);
+                        }
+                        if let Some(parsed) = &mut self.parsed
+                            && let Some(result) = self.tree_editor.apply(parsed)
+                        {
+                            match result {
+                                Ok(()) => self.after_edit(),
+                                Err(e) => self.warning = e,
+                            }
+                        }
                     } else {
                         // 使用文本框
                         ScrollArea::vertical()
@@ -178,7 +314,8 @@ impl super::ToolItem for JsonConverter {
                             .auto_shrink([false; 2])
                             .show(ui, |ui| {
                                 ui.add(Label::new(
-                                    RichText::new(&self.converted).text_style(TextStyle::Monospace),
+                                    RichText::new(self.displayed_text())
+                                        .text_style(TextStyle::Monospace),
                                 ));
                                 /*
                                 ui.add(
@@ -209,48 +346,37 @@ impl super::ToolItem for JsonConverter {
                 });
 
                 if btn_response.inner.clicked() {
-                    self.copied_prompt = "";
-                    self.use_json_tree = false;
-                    let input = if self.pythonic_style {
-                        self.input.replace("'", "\"").replace("None", "null")
-                    } else {
-                        self.input.clone()
-                    };
-                    match self.conversion {
-                        Conversion::Deserialize => match serde_json::from_str::<String>(&input) {
-                            Ok(v) => self.converted = v,
-                            Err(e) => self.warning = e.to_string(),
-                        },
-                        Conversion::Serialize => match serde_json::to_string(&input) {
-                            Ok(v) => self.converted = v,
-                            Err(e) => self.warning = e.to_string(),
-                        },
-                        _ => self.converted = input,
-                    }
-                    match serde_json::from_str::<serde_json::Value>(&self.converted) {
-                        Ok(v) => match self.format {
-                            Formatter::Pretty => match serde_json::to_string_pretty(&v) {
-                                Ok(c) => {
-                                    self.converted = c;
-                                    self.use_json_tree = true;
-                                    self.warning = String::new();
-                                }
-                                Err(e) => self.warning = e.to_string(),
-                            },
-                            Formatter::Minimize => match serde_json::to_string(&v) {
-                                Ok(c) => {
-                                    self.converted = c;
-                                    self.warning = String::new();
-                                }
-                                Err(e) => self.warning = e.to_string(),
-                            },
-                            _ => self.warning = String::new(),
-                        },
-                        Err(e) => self.warning = e.to_string(),
-                    }
+                    self.process();
                 }
                 ui.add_space(16.0);
 
+                // 输入语法
+                ui.label("输入");
+                Frame::default()
+                    .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+                    .inner_margin(4)
+                    .corner_radius(6)
+                    .show(ui, |ui| {
+                        ui.selectable_value(&mut self.options.input_mode, InputMode::Json, "JSON");
+                        ui.selectable_value(
+                            &mut self.options.input_mode,
+                            InputMode::Json5,
+                            "JSON5 / JSONC",
+                        )
+                        .on_hover_ui(|ui| {
+                            ui.label("允许注释、末尾逗号、不加引号的键和单引号字符串");
+                        });
+                        ui.selectable_value(
+                            &mut self.options.input_mode,
+                            InputMode::Ndjson,
+                            "NDJSON",
+                        )
+                        .on_hover_ui(|ui| {
+                            ui.label("每行一个 JSON，逐行解析后合并为数组");
+                        });
+                    });
+                ui.add_space(16.0);
+
                 // 转换选项
                 ui.label("转换");
                 Frame::default()
@@ -258,9 +384,9 @@ impl super::ToolItem for JsonConverter {
                     .inner_margin(4)
                     .corner_radius(6)
                     .show(ui, |ui| {
-                        ui.selectable_value(&mut self.conversion, Conversion::None, "无");
+                        ui.selectable_value(&mut self.options.conversion, Conversion::None, "无");
                         ui.selectable_value(
-                            &mut self.conversion,
+                            &mut self.options.conversion,
                             Conversion::Deserialize,
                             "反序列化",
                         )
@@ -268,11 +394,15 @@ impl super::ToolItem for JsonConverter {
                             ui.label("输入需要为有效的字符串，包含两端的引号（\"）");
                         });
                         if ui
-                            .selectable_value(&mut self.conversion, Conversion::Serialize, "序列化")
+                            .selectable_value(
+                                &mut self.options.conversion,
+                                Conversion::Serialize,
+                                "序列化",
+                            )
                             .clicked()
                         {
                             // 序列化后无法格式化 json
-                            self.format = Formatter::None;
+                            self.options.format = Formatter::None;
                         }
                     });
                 ui.add_space(16.0);
@@ -284,45 +414,458 @@ impl super::ToolItem for JsonConverter {
                     .inner_margin(4)
                     .corner_radius(6)
                     .show(ui, |ui| {
-                        ui.selectable_value(&mut self.format, Formatter::None, "无");
-                        ui.selectable_value(&mut self.format, Formatter::Pretty, "展开");
-                        ui.selectable_value(&mut self.format, Formatter::Minimize, "压缩");
+                        ui.selectable_value(&mut self.options.format, Formatter::None, "无");
+                        ui.selectable_value(&mut self.options.format, Formatter::Pretty, "展开");
+                        ui.selectable_value(&mut self.options.format, Formatter::Minimize, "压缩");
+                        ui.selectable_value(
+                            &mut self.options.format,
+                            Formatter::Canonical,
+                            "规范化 (JCS)",
+                        )
+                        .on_hover_ui(|ui| {
+                            ui.label(
+                                "RFC 8785：键排序、数值规范化、无空白，相同内容的输出逐字节相同",
+                            );
+                        });
                     });
+                ui.menu_button("输出选项", |ui| self.output_options_ui(ui));
                 ui.add_space(16.0);
 
                 ui.checkbox(
-                    &mut self.pythonic_style,
+                    &mut self.options.pythonic_style,
                     "Python dict 风格（如 {'key': None}）",
                 );
+                ui.checkbox(&mut self.options.python_output, "输出 Python 字面量");
+                ui.add_space(16.0);
+
+                ui.label("生成类型");
+                ComboBox::from_id_salt("types")
+                    .selected_text(self.options.types.map_or("无", |language| language.label()))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.options.types, None, "无");
+                        for language in json::ALL_LANGUAGES {
+                            ui.selectable_value(
+                                &mut self.options.types,
+                                Some(language),
+                                language.label(),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(
+                        "从结果推断类型定义；结果为数组时每个元素作为一个示例，合并推断可选字段",
+                    );
                 //ui.checkbox(&mut self.use_single_quotes, "使用单引号");
             },
         );
         ui.add_space(8.0);
 
-        // 警告提示
+        // 警告提示，有出错位置时点击跳转
         ui.horizontal(|ui| {
-            ui.add(Label::new(
-                RichText::new(&self.warning).color(style::warn_color(ui.visuals().dark_mode)),
-            ));
+            let warning =
+                RichText::new(&self.warning).color(style::warn_color(ui.visuals().dark_mode));
+            match &self.error {
+                Some(error) => {
+                    if ui
+                        .add(Label::new(warning).sense(Sense::click()))
+                        .on_hover_cursor(CursorIcon::PointingHand)
+                        .on_hover_text("点击跳转到出错位置")
+                        .clicked()
+                    {
+                        self.jump_to = Some(error.char_index(&self.input));
+                        ui.ctx().request_repaint();
+                    }
+                }
+                None => {
+                    ui.add(Label::new(warning));
+                }
+            }
         });
+        if let Some(hint) = self.hint {
+            ui.weak(format!("💡 {hint}"));
+        }
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            input: self.input.clone(),
+            options: self.options.clone(),
+            query: self.query.clone(),
+            diff: self.diff,
+            diff_state: self.diff_view.save(),
+            schema: self.schema_view.save(),
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.input = state.input;
+        self.options = state.options;
+        self.query = state.query;
+        self.diff = state.diff;
+        self.diff_view.load(state.diff_state, &self.diff_options());
+        self.schema_view.load(state.schema);
+        if !self.input.is_empty() {
+            self.process();
+        }
+    }
+
+    fn actions(&self) -> &[ToolAction] {
+        ACTIONS
+    }
+
+    fn run_action(&mut self, id: &str, ctx: &Context) {
+        match id {
+            "process" => self.process(),
+            // 剪贴板识别后的路由也走这里，先清除上次的输入语法
+            "json" | "python" | "deserialize" => {
+                self.options.input_mode = InputMode::Json;
+                self.options.conversion = if id == "deserialize" {
+                    Conversion::Deserialize
+                } else {
+                    Conversion::None
+                };
+                self.options.pythonic_style = id == "python";
+                self.process();
+            }
+            "json5" | "ndjson" => {
+                self.options.input_mode = if id == "json5" {
+                    InputMode::Json5
+                } else {
+                    InputMode::Ndjson
+                };
+                self.options.pythonic_style = false;
+                self.process();
+            }
+            "to_python" => {
+                self.options.python_output = true;
+                self.process();
+            }
+            "rust_types" | "typescript_types" | "python_types" => {
+                self.options.types = Some(match id {
+                    "rust_types" => Language::Rust,
+                    "typescript_types" => Language::TypeScript,
+                    _ => Language::Python,
+                });
+                self.process();
+            }
+            "to_yaml" => {
+                self.options.target = DataFormat::Yaml;
+                self.process();
+            }
+            "pretty" => {
+                self.options.format = Formatter::Pretty;
+                self.process();
+            }
+            "minimize" => {
+                self.options.format = Formatter::Minimize;
+                self.process();
+            }
+            "canonical" => {
+                self.options.format = Formatter::Canonical;
+                self.process();
+            }
+            "copy" => self.copy_result(ctx),
+            "diff" => {
+                self.diff = true;
+                if self.diff_view.left.is_empty() {
+                    self.diff_view.left = self.input.clone();
+                }
+                self.diff_view.compare(&self.diff_options());
+            }
+            "infer_schema" => {
+                if let Some(parsed) = &self.parsed {
+                    self.schema_view.infer(parsed);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_owned();
+        self.process();
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.input.clone())
     }
 }
 
-impl Default for JsonConverter {
-    fn default() -> Self {
-        JsonConverter {
-            input: String::new(),
-            converted: String::new(),
-            format: Formatter::Pretty,
-            conversion: Conversion::None,
-            warning: String::new(),
-            use_json_tree: false,
-            search_input: String::new(),
-            copied_prompt: "",
-            #[cfg(not(target_arch = "wasm32"))]
-            prompt_vanish_at: Instant::now(),
-            pythonic_style: false,
-            //use_single_quotes: false,
+impl JsonConverter {
+    fn process(&mut self) {
+        self.copied_prompt = "";
+        let processed = json::process(&self.input, &self.options);
+        self.converted = processed.output;
+        self.use_json_tree = processed.pretty;
+        // NDJSON 部分行出错时仍然展示解析成功的行
+        self.parsed = if self.options.target == DataFormat::Json {
+            serde_json::from_str(&self.converted).ok()
+        } else {
+            None
+        };
+        self.warning = processed.warning.unwrap_or_default();
+        self.error = processed.error;
+        self.hint = processed.hint;
+        self.tree_editor.clear();
+        self.run_query();
+        self.schema_view.validate(self.parsed.as_ref());
+    }
+
+    /// 在树形视图中编辑后，按当前选项重新生成文本结果，与处理时的输出一致
+    fn after_edit(&mut self) {
+        let Some(parsed) = &mut self.parsed else {
+            return;
+        };
+        json::normalize(parsed, &self.options);
+        let processed = json::render(parsed, &self.options);
+        self.converted = processed.output;
+        self.use_json_tree = processed.pretty;
+        self.warning = processed.warning.unwrap_or_default();
+        self.error = None;
+        self.hint = None;
+        self.run_query();
+        self.schema_view.validate(self.parsed.as_ref());
+    }
+
+    /// 缩进宽度和输出前的规范化，点击处理后生效
+    fn output_options_ui(&mut self, ui: &mut Ui) {
+        let options = &mut self.options;
+        ui.horizontal(|ui| {
+            ui.label("缩进");
+            ui.add(
+                DragValue::new(&mut options.indent)
+                    .range(1..=8)
+                    .suffix(" 空格"),
+            );
+        });
+        ui.separator();
+        ui.checkbox(&mut options.sort_keys, "键排序");
+        ui.checkbox(&mut options.sort_arrays, "标量数组排序")
+            .on_hover_text("只排序元素都是 null、布尔值、数值或字符串的数组");
+        ui.checkbox(&mut options.drop_nulls, "删除值为 null 的键");
+        ui.checkbox(&mut options.drop_empty, "删除值为空对象或空数组的键");
+        ui.checkbox(&mut options.escape_non_ascii, "转义非 ASCII 字符")
+            .on_hover_text("输出为 \\uXXXX，规范化 (JCS) 时不转义");
+    }
+
+    /// 源格式和目标格式，有二进制格式时可选 hex 或 base64
+    fn formats_ui(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+        ui.label("格式");
+        changed |= format_combo(ui, "source_format", &mut self.options.source);
+        if ui
+            .button("⇄")
+            .on_hover_text("交换源格式和目标格式")
+            .clicked()
+        {
+            let options = &mut self.options;
+            std::mem::swap(&mut options.source, &mut options.target);
+            changed = true;
+        }
+        changed |= format_combo(ui, "target_format", &mut self.options.target);
+        if self.options.source.is_binary() || self.options.target.is_binary() {
+            ui.add_space(8.0);
+            let encoding = &mut self.options.binary_encoding;
+            changed |= ui
+                .selectable_value(encoding, BinaryEncoding::Hex, "Hex")
+                .changed();
+            changed |= ui
+                .selectable_value(encoding, BinaryEncoding::Base64, "Base64")
+                .changed();
+        }
+        changed
+    }
+
+    /// 对比模式只使用 Python dict 风格选项，两侧都按 JSON 文档解析
+    fn diff_options(&self) -> json::Options {
+        json::Options {
+            pythonic_style: self.options.pythonic_style,
+            ..Default::default()
+        }
+    }
+
+    /// 输入框，解析出错时高亮出错的行和字符
+    fn input_ui(&mut self, ui: &mut Ui, rows: usize) {
+        let highlight = self
+            .error
+            .as_ref()
+            .map(|error| error_ranges(&self.input, error));
+        let warn_color = style::warn_color(ui.visuals().dark_mode);
+        let mut layouter = |ui: &Ui, text: &dyn TextBuffer, wrap_width: f32| {
+            let mut job = highlight_job(ui, text.as_str(), highlight.clone(), warn_color);
+            job.wrap.max_width = wrap_width;
+            ui.fonts(|fonts| fonts.layout_job(job))
+        };
+        let output = TextEdit::multiline(&mut self.input)
+            .desired_width(f32::INFINITY)
+            .desired_rows(rows)
+            .code_editor()
+            .layouter(&mut layouter)
+            .show(ui);
+        if output.response.changed() {
+            // 位置已经不准确
+            self.error = None;
         }
+        if let Some(index) = self.jump_to.take() {
+            let cursor = CCursor::new(index);
+            let mut state = output.state;
+            state.cursor.set_char_range(Some(CCursorRange::one(cursor)));
+            state.store(ui.ctx(), output.response.id);
+            output.response.request_focus();
+            let rect = output
+                .galley
+                .pos_from_cursor(cursor)
+                .translate(output.galley_pos.to_vec2());
+            ui.scroll_to_rect(rect.expand(8.0), Some(Align::Center));
+        }
+    }
+
+    fn run_query(&mut self) {
+        self.queried = match &self.parsed {
+            Some(value) if !self.query.trim().is_empty() => Some(
+                json::query(value, &self.query)
+                    .map(|mut results| {
+                        let count = results.len();
+                        let value = if count == 1 {
+                            results.remove(0)
+                        } else {
+                            Value::Array(results)
+                        };
+                        (value, count)
+                    })
+                    .map_err(|e| e.to_string()),
+            ),
+            _ => None,
+        };
     }
+
+    /// 查询框，以及查询结果个数或错误
+    fn query_ui(&mut self, ui: &mut Ui) {
+        ui.scope(|ui| {
+            ui.spacing_mut().item_spacing = (4.0, 4.0).into();
+            ui.horizontal(|ui| {
+                ui.label("查询：");
+                let response = ui.add(
+                    TextEdit::singleline(&mut self.query)
+                        .hint_text("$.items[*].id 或 .items[] | select(.n > 1) | .id")
+                        .desired_width(320.0),
+                );
+                let cleared = ui.add(Button::new("❌").frame(false)).clicked();
+                if cleared {
+                    self.query.clear();
+                }
+                if response.changed() || cleared {
+                    self.run_query();
+                }
+                match &self.queried {
+                    Some(Ok((_, count))) => {
+                        ui.weak(format!("{count} 个结果"));
+                    }
+                    Some(Err(e)) => {
+                        ui.label(RichText::new(e).color(style::warn_color(ui.visuals().dark_mode)));
+                    }
+                    None => {}
+                }
+            });
+        });
+    }
+
+    /// 文本视图中展示的内容，有查询时为查询结果
+    fn displayed_text(&self) -> String {
+        match &self.queried {
+            Some(Ok((value, _))) => {
+                let format = match self.options.format {
+                    Formatter::None => Formatter::Pretty,
+                    format => format,
+                };
+                let options = json::Options {
+                    format,
+                    ..self.options.clone()
+                };
+                json::to_json(value, &options)
+            }
+            _ => self.converted.clone(),
+        }
+    }
+
+    fn copy_result(&mut self, ctx: &Context) {
+        ctx.copy_text(self.displayed_text());
+        self.copied_prompt = "已复制";
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.prompt_vanish_at = Some(Instant::now() + Duration::from_secs(2));
+        }
+    }
+}
+
+/// 格式下拉框，返回是否修改
+fn format_combo(ui: &mut Ui, id_salt: &str, format: &mut DataFormat) -> bool {
+    let mut changed = false;
+    ComboBox::from_id_salt(id_salt)
+        .selected_text(format.label())
+        .show_ui(ui, |ui| {
+            for option in json::ALL_FORMATS {
+                changed |= ui
+                    .selectable_value(format, option, option.label())
+                    .changed();
+            }
+        });
+    changed
+}
+
+/// 出错行和出错字符在输入中的字节范围
+fn error_ranges(input: &str, error: &SyntaxError) -> (Range<usize>, Range<usize>) {
+    let index = error.char_index(input);
+    let start = input
+        .char_indices()
+        .nth(index)
+        .map_or(input.len(), |(offset, _)| offset);
+    let end = input[start..]
+        .chars()
+        .next()
+        .filter(|&c| c != '\n')
+        .map_or(start, |c| start + c.len_utf8());
+    let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = input[start..].find('\n').map_or(input.len(), |i| start + i);
+    (line_start..line_end, start..end)
+}
+
+/// 等宽字体的排版，`highlight` 为出错行和出错字符的字节范围
+fn highlight_job(
+    ui: &Ui,
+    text: &str,
+    highlight: Option<(Range<usize>, Range<usize>)>,
+    color: Color32,
+) -> LayoutJob {
+    let plain = TextFormat::simple(
+        TextStyle::Monospace.resolve(ui.style()),
+        ui.visuals().text_color(),
+    );
+    let mut job = LayoutJob::default();
+    // 编辑后范围可能越界，此时不再高亮
+    let Some((line, at)) = highlight.filter(|(line, at)| {
+        [line.start, line.end, at.start, at.end]
+            .iter()
+            .all(|&i| text.is_char_boundary(i))
+    }) else {
+        job.append(text, 0.0, plain);
+        return job;
+    };
+    let line_format = TextFormat {
+        background: color.gamma_multiply(0.15),
+        ..plain.clone()
+    };
+    let at_format = TextFormat {
+        background: color.gamma_multiply(0.4),
+        underline: Stroke::new(1.0, color),
+        ..plain.clone()
+    };
+    job.append(&text[..line.start], 0.0, plain.clone());
+    job.append(&text[line.start..at.start], 0.0, line_format.clone());
+    job.append(&text[at.clone()], 0.0, at_format);
+    job.append(&text[at.end..line.end], 0.0, line_format);
+    job.append(&text[line.end..], 0.0, plain);
+    job
 }
@@ -0,0 +1,287 @@
+use eframe::egui::{
+    Button, CollapsingHeader, Color32, Label, RichText, ScrollArea, Sense, TextEdit, TextStyle,
+    TextWrapMode, Ui,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    engine::json::{self, Change, ChangeKind, Diff},
+    style,
+};
+
+/// 预览中值的最大字符数
+const PREVIEW_CHARS: usize = 80;
+
+/// 对比模式：左右两侧各输入一个文档，下方以树形展示差异
+#[derive(Default)]
+pub(super) struct DiffView {
+    pub(super) left: String,
+    right: String,
+    ignore_array_order: bool,
+    only_changes: bool,
+    result: Option<Result<Compared, String>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct SavedDiff {
+    left: String,
+    right: String,
+    ignore_array_order: bool,
+    only_changes: bool,
+}
+
+/// 对比结果
+struct Compared {
+    diff: Diff,
+    changes: Vec<Change>,
+    /// 右侧文档，用于把路径转为 JSONPath
+    right: Value,
+}
+
+struct Colors {
+    added: Color32,
+    removed: Color32,
+    changed: Color32,
+}
+
+impl DiffView {
+    pub(super) fn show(&mut self, ui: &mut Ui, options: &json::Options) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .checkbox(&mut self.ignore_array_order, "忽略数组顺序")
+                .changed();
+            ui.checkbox(&mut self.only_changes, "只显示差异");
+            if ui.button("⇄ 交换左右").clicked() {
+                std::mem::swap(&mut self.left, &mut self.right);
+                changed = true;
+            }
+            let patch = match &self.result {
+                Some(Ok(compared)) => Some(json::to_patch(&compared.diff)),
+                _ => None,
+            };
+            if ui
+                .add_enabled(patch.is_some(), Button::new("复制 JSON Patch"))
+                .on_hover_text("RFC 6902，应用到左侧文档后得到右侧文档")
+                .clicked()
+                && let Some(patch) = patch
+            {
+                ui.ctx()
+                    .copy_text(serde_json::to_string_pretty(&patch).unwrap_or_default());
+            }
+        });
+        ui.columns(2, |columns| {
+            let [left, right] = columns else {
+                return;
+            };
+            for (column, label, input) in [
+                (left, "左侧（旧）", &mut self.left),
+                (right, "右侧（新）", &mut self.right),
+            ] {
+                column.label(label);
+                ScrollArea::vertical()
+                    .id_salt(label)
+                    .max_height(260.0)
+                    .show(column, |ui| {
+                        changed |= ui
+                            .add(
+                                TextEdit::multiline(input)
+                                    .desired_width(f32::INFINITY)
+                                    .desired_rows(12)
+                                    .code_editor(),
+                            )
+                            .changed();
+                    });
+            }
+        });
+        if changed {
+            self.compare(options);
+        }
+        ui.separator();
+        self.result_ui(ui);
+    }
+
+    /// 使用与转换模式相同的选项解析两侧输入并对比
+    pub(super) fn compare(&mut self, options: &json::Options) {
+        if self.left.trim().is_empty() || self.right.trim().is_empty() {
+            self.result = None;
+            return;
+        }
+        self.result = Some((|| {
+            let left = json::parse(&self.left, options).map_err(|e| format!("左侧：{e}"))?;
+            let right = json::parse(&self.right, options).map_err(|e| format!("右侧：{e}"))?;
+            let diff = json::diff(&left, &right, self.ignore_array_order);
+            let changes = diff.changes();
+            Ok(Compared {
+                diff,
+                changes,
+                right,
+            })
+        })());
+    }
+
+    pub(super) fn save(&self) -> SavedDiff {
+        SavedDiff {
+            left: self.left.clone(),
+            right: self.right.clone(),
+            ignore_array_order: self.ignore_array_order,
+            only_changes: self.only_changes,
+        }
+    }
+
+    pub(super) fn load(&mut self, saved: SavedDiff, options: &json::Options) {
+        self.left = saved.left;
+        self.right = saved.right;
+        self.ignore_array_order = saved.ignore_array_order;
+        self.only_changes = saved.only_changes;
+        self.compare(options);
+    }
+
+    fn result_ui(&self, ui: &mut Ui) {
+        let dark_mode = ui.visuals().dark_mode;
+        let colors = Colors {
+            added: style::diff_added_color(dark_mode),
+            removed: style::diff_removed_color(dark_mode),
+            changed: style::diff_changed_color(dark_mode),
+        };
+        let Compared {
+            diff,
+            changes,
+            right,
+        } = match &self.result {
+            Some(Ok(compared)) => compared,
+            Some(Err(e)) => {
+                ui.label(RichText::new(e).color(style::warn_color(dark_mode)));
+                return;
+            }
+            None => return,
+        };
+        if diff.is_same() {
+            ui.label("两侧内容相同");
+            return;
+        }
+        let count = |f: fn(&ChangeKind) -> bool| changes.iter().filter(|c| f(&c.kind)).count();
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!(
+                    "+ {} 处新增",
+                    count(|kind| matches!(kind, ChangeKind::Added(_)))
+                ))
+                .color(colors.added),
+            );
+            ui.label(
+                RichText::new(format!(
+                    "- {} 处删除",
+                    count(|kind| matches!(kind, ChangeKind::Removed(_)))
+                ))
+                .color(colors.removed),
+            );
+            ui.label(
+                RichText::new(format!(
+                    "~ {} 处修改",
+                    count(|kind| matches!(kind, ChangeKind::Changed { .. }))
+                ))
+                .color(colors.changed),
+            );
+        });
+        ScrollArea::both()
+            .id_salt("diff_tree")
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                node_ui(ui, "$", "", diff, right, self.only_changes, &colors);
+            });
+    }
+}
+
+/// 显示一个节点，容器节点可以折叠；点击叶子节点复制路径
+fn node_ui(
+    ui: &mut Ui,
+    label: &str,
+    pointer: &str,
+    diff: &Diff,
+    right: &Value,
+    only_changes: bool,
+    colors: &Colors,
+) {
+    let (marker, color, text) = match diff {
+        Diff::Same(value) => {
+            if only_changes {
+                return;
+            }
+            (" ", ui.visuals().weak_text_color(), preview(value))
+        }
+        Diff::Added(value) => ("+", colors.added, preview(value)),
+        Diff::Removed(value) => ("-", colors.removed, preview(value)),
+        Diff::Changed { old, new } => (
+            "~",
+            colors.changed,
+            format!("{} → {}", preview(old), preview(new)),
+        ),
+        Diff::Object(children) => {
+            let children = children.iter().map(|(key, child)| {
+                (
+                    key.clone(),
+                    format!("{pointer}/{}", json::escape_token(key)),
+                    child,
+                )
+            });
+            container_ui(ui, label, pointer, children, right, only_changes, colors);
+            return;
+        }
+        Diff::Array(children) => {
+            let children = children
+                .iter()
+                .map(|(index, child)| (format!("[{index}]"), format!("{pointer}/{index}"), child));
+            container_ui(ui, label, pointer, children, right, only_changes, colors);
+            return;
+        }
+    };
+    let response = ui
+        .add(
+            Label::new(
+                RichText::new(format!("{marker} {label}: {text}"))
+                    .text_style(TextStyle::Monospace)
+                    .color(color),
+            )
+            .wrap_mode(TextWrapMode::Extend)
+            .sense(Sense::click()),
+        )
+        .on_hover_text("点击复制路径");
+    if response.clicked() {
+        ui.ctx().copy_text(json::path_from_pointer(right, pointer));
+    }
+}
+
+fn container_ui<'a>(
+    ui: &mut Ui,
+    label: &str,
+    pointer: &str,
+    children: impl Iterator<Item = (String, String, &'a Diff)>,
+    right: &Value,
+    only_changes: bool,
+    colors: &Colors,
+) {
+    CollapsingHeader::new(
+        RichText::new(format!("~ {label}"))
+            .text_style(TextStyle::Monospace)
+            .color(colors.changed),
+    )
+    .id_salt(("diff", pointer))
+    .default_open(true)
+    .show(ui, |ui| {
+        for (label, pointer, child) in children {
+            node_ui(ui, &label, &pointer, child, right, only_changes, colors);
+        }
+    });
+}
+
+/// 单行预览，过长时截断
+fn preview(value: &Value) -> String {
+    let text = serde_json::to_string(value).unwrap_or_default();
+    match text.char_indices().nth(PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
@@ -0,0 +1,150 @@
+use eframe::egui::{
+    Button, CollapsingHeader, CursorIcon, Label, RichText, ScrollArea, Sense, TextEdit, Ui,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    engine::json::{self, Violation},
+    style,
+};
+
+/// JSON Schema 校验：Schema 有变化或处理结果更新时重新校验
+#[derive(Default)]
+pub(super) struct SchemaView {
+    schema: String,
+    result: Option<Result<Vec<Violation>, String>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct SavedSchema {
+    schema: String,
+}
+
+impl SchemaView {
+    pub(super) fn show(&mut self, ui: &mut Ui, instance: &Value) {
+        CollapsingHeader::new("JSON Schema 校验")
+            .default_open(!self.schema.is_empty())
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("从结果推断")
+                        .on_hover_text("按当前结果生成 draft 2020-12 Schema，覆盖下方内容")
+                        .clicked()
+                    {
+                        self.infer(instance);
+                    }
+                    if ui
+                        .add_enabled(!self.schema.is_empty(), Button::new("复制 Schema"))
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(self.schema.clone());
+                    }
+                    if ui.button("清空").clicked() {
+                        self.schema.clear();
+                        self.result = None;
+                    }
+                });
+                ScrollArea::vertical()
+                    .id_salt("schema")
+                    .max_height(160.0)
+                    .show(ui, |ui| {
+                        let response = ui.add(
+                            TextEdit::multiline(&mut self.schema)
+                                .hint_text("粘贴 JSON Schema（draft 2020-12）")
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(6)
+                                .code_editor(),
+                        );
+                        if response.changed() {
+                            self.validate(Some(instance));
+                        }
+                    });
+                self.result_ui(ui, instance);
+            });
+    }
+
+    /// 用当前 Schema 校验，Schema 为空或没有可校验的值时清除结果
+    pub(super) fn validate(&mut self, instance: Option<&Value>) {
+        self.result = match instance {
+            Some(instance) if !self.schema.trim().is_empty() => {
+                Some(serde_json::from_str(&self.schema).map_or_else(
+                    |e| Err(format!("Schema 不是有效的 JSON：{e}")),
+                    |schema| json::validate_schema(&schema, instance),
+                ))
+            }
+            _ => None,
+        };
+    }
+
+    pub(super) fn infer(&mut self, instance: &Value) {
+        self.schema =
+            serde_json::to_string_pretty(&json::infer_schema(instance)).unwrap_or_default();
+        self.validate(Some(instance));
+    }
+
+    /// `pointer` 处的校验错误
+    pub(super) fn messages_at(&self, pointer: &str) -> Vec<&str> {
+        match &self.result {
+            Some(Ok(violations)) => violations
+                .iter()
+                .filter(|v| v.pointer == pointer)
+                .map(|v| v.message.as_str())
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    pub(super) fn save(&self) -> SavedSchema {
+        SavedSchema {
+            schema: self.schema.clone(),
+        }
+    }
+
+    pub(super) fn load(&mut self, saved: SavedSchema) {
+        self.schema = saved.schema;
+        self.result = None;
+    }
+
+    fn result_ui(&self, ui: &mut Ui, instance: &Value) {
+        let warn_color = style::warn_color(ui.visuals().dark_mode);
+        let violations = match &self.result {
+            Some(Ok(violations)) => violations,
+            Some(Err(e)) => {
+                ui.label(RichText::new(e).color(warn_color));
+                return;
+            }
+            None => return,
+        };
+        if violations.is_empty() {
+            ui.label(
+                RichText::new("✔ 符合 Schema").color(style::prompt_color(ui.visuals().dark_mode)),
+            );
+            return;
+        }
+        ui.label(RichText::new(format!("{} 处不符合 Schema", violations.len())).color(warn_color));
+        ScrollArea::vertical()
+            .id_salt("violations")
+            .max_height(120.0)
+            .show(ui, |ui| {
+                for violation in violations {
+                    let path = json::path_from_pointer(instance, &violation.pointer);
+                    if ui
+                        .add(
+                            Label::new(
+                                RichText::new(format!("{path}: {}", violation.message))
+                                    .color(warn_color),
+                            )
+                            .sense(Sense::click()),
+                        )
+                        .on_hover_cursor(CursorIcon::PointingHand)
+                        .on_hover_text("点击复制路径")
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(path);
+                    }
+                }
+            });
+    }
+}
@@ -0,0 +1,267 @@
+use eframe::egui::{Button, Context, Id, Modal, RichText, TextEdit, Ui};
+use serde_json::Value;
+
+use crate::{
+    engine::json::{self, Edit},
+    style,
+};
+
+/// 撤销栈的最大深度
+const UNDO_LIMIT: usize = 100;
+
+/// 树形视图中的编辑：右键菜单、填写键和值的对话框，以及撤销栈
+#[derive(Default)]
+pub(super) struct TreeEditor {
+    undo: Vec<Value>,
+    redo: Vec<Value>,
+    form: Option<Form>,
+    /// 菜单或对话框中选择的编辑，树形视图绘制完成后执行
+    pending: Option<Edit>,
+}
+
+/// 需要填写键或值的编辑
+struct Form {
+    kind: FormKind,
+    pointer: String,
+    key: String,
+    value: String,
+    error: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FormKind {
+    Replace,
+    Rename,
+    AddKey,
+    Append,
+    InsertBefore,
+}
+
+impl FormKind {
+    fn title(self) -> &'static str {
+        match self {
+            FormKind::Replace => "编辑值",
+            FormKind::Rename => "重命名键",
+            FormKind::AddKey => "添加键",
+            FormKind::Append => "追加元素",
+            FormKind::InsertBefore => "在此前插入",
+        }
+    }
+
+    fn has_key(self) -> bool {
+        matches!(self, FormKind::Rename | FormKind::AddKey)
+    }
+
+    fn has_value(self) -> bool {
+        self != FormKind::Rename
+    }
+}
+
+impl TreeEditor {
+    /// 右键菜单中的编辑操作，`root` 为整个文档，`value` 为 `pointer` 处的值
+    pub(super) fn menu_ui(&mut self, ui: &mut Ui, root: &Value, pointer: &str, value: &Value) {
+        ui.separator();
+        if ui.button("编辑值…").clicked() {
+            let text = serde_json::to_string_pretty(value).unwrap_or_default();
+            self.open(FormKind::Replace, pointer, String::new(), text);
+            ui.close();
+        }
+        let parent = pointer
+            .rsplit_once('/')
+            .and_then(|(parent, token)| Some((root.pointer(parent)?, token)));
+        if let Some((Value::Object(_), token)) = parent
+            && ui.button("重命名键…").clicked()
+        {
+            let key = token.replace("~1", "/").replace("~0", "~");
+            self.open(FormKind::Rename, pointer, key, String::new());
+            ui.close();
+        }
+        if let Some((Value::Array(items), token)) = parent {
+            let index = token.parse::<usize>().unwrap_or_default();
+            if ui.button("在此前插入…").clicked() {
+                self.open(
+                    FormKind::InsertBefore,
+                    pointer,
+                    String::new(),
+                    String::new(),
+                );
+                ui.close();
+            }
+            if ui.add_enabled(index > 0, Button::new("上移")).clicked() {
+                self.pending = Some(Edit::Move {
+                    pointer: pointer.to_owned(),
+                    to: index - 1,
+                });
+                ui.close();
+            }
+            if ui
+                .add_enabled(index + 1 < items.len(), Button::new("下移"))
+                .clicked()
+            {
+                self.pending = Some(Edit::Move {
+                    pointer: pointer.to_owned(),
+                    to: index + 1,
+                });
+                ui.close();
+            }
+        }
+        let child = match value {
+            Value::Object(_) => Some(FormKind::AddKey),
+            Value::Array(_) => Some(FormKind::Append),
+            _ => None,
+        };
+        if let Some(kind) = child
+            && ui.button(format!("{}…", kind.title())).clicked()
+        {
+            self.open(kind, pointer, String::new(), String::new());
+            ui.close();
+        }
+        if !pointer.is_empty() && ui.button("删除").clicked() {
+            self.pending = Some(Edit::Remove {
+                pointer: pointer.to_owned(),
+            });
+            ui.close();
+        }
+    }
+
+    /// 填写键和值的对话框，`root` 为正在编辑的文档
+    pub(super) fn form_ui(&mut self, ctx: &Context, root: &Value) {
+        let Some(form) = &mut self.form else {
+            return;
+        };
+        let mut submitted = false;
+        let mut cancelled = false;
+        let modal = Modal::new(Id::new("json_tree_edit")).show(ctx, |ui| {
+            ui.set_width(420.0);
+            ui.heading(form.kind.title());
+            ui.weak(json::path_from_pointer(root, &form.pointer));
+            if form.kind.has_key() {
+                ui.label("键");
+                ui.add(TextEdit::singleline(&mut form.key).desired_width(f32::INFINITY));
+            }
+            if form.kind.has_value() {
+                ui.label("值");
+                ui.add(
+                    TextEdit::multiline(&mut form.value)
+                        .hint_text("JSON，如 \"文本\"、1、true、null、{}")
+                        .desired_width(f32::INFINITY)
+                        .desired_rows(6)
+                        .code_editor(),
+                );
+            }
+            if !form.error.is_empty() {
+                ui.label(
+                    RichText::new(&form.error).color(style::warn_color(ui.visuals().dark_mode)),
+                );
+            }
+            ui.horizontal(|ui| {
+                submitted = ui.button("确定").clicked();
+                cancelled = ui.button("取消").clicked();
+            });
+        });
+        if submitted {
+            match form.edit() {
+                Ok(edit) => {
+                    self.pending = Some(edit);
+                    self.form = None;
+                }
+                Err(e) => form.error = e,
+            }
+        } else if cancelled || modal.should_close() {
+            self.form = None;
+        }
+    }
+
+    /// 执行待处理的编辑，没有编辑时返回 `None`
+    pub(super) fn apply(&mut self, root: &mut Value) -> Option<Result<(), String>> {
+        let edit = self.pending.take()?;
+        let before = root.clone();
+        Some(json::apply_edit(root, edit).map(|()| {
+            if self.undo.len() == UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+            self.undo.push(before);
+            self.redo.clear();
+        }))
+    }
+
+    /// 撤销和重做按钮，只在编辑过之后显示。返回文档是否改变
+    pub(super) fn toolbar_ui(&mut self, ui: &mut Ui, root: &mut Value) -> bool {
+        if self.undo.is_empty() && self.redo.is_empty() {
+            return false;
+        }
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!self.undo.is_empty(), Button::new("↶ 撤销"))
+                .clicked()
+                && let Some(previous) = self.undo.pop()
+            {
+                self.redo.push(std::mem::replace(root, previous));
+                changed = true;
+            }
+            if ui
+                .add_enabled(!self.redo.is_empty(), Button::new("↷ 重做"))
+                .clicked()
+                && let Some(next) = self.redo.pop()
+            {
+                self.undo.push(std::mem::replace(root, next));
+                changed = true;
+            }
+            ui.weak("结果已修改，重新处理会丢弃修改");
+        });
+        changed
+    }
+
+    /// 重新处理后清除撤销栈
+    pub(super) fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn open(&mut self, kind: FormKind, pointer: &str, key: String, value: String) {
+        self.form = Some(Form {
+            kind,
+            pointer: pointer.to_owned(),
+            key,
+            value,
+            error: String::new(),
+        });
+    }
+}
+
+impl Form {
+    fn edit(&self) -> Result<Edit, String> {
+        let value = if self.kind.has_value() {
+            serde_json::from_str(&self.value)
+                .map_err(|e| format!("值不是有效的 JSON，字符串需要加双引号：{e}"))?
+        } else {
+            Value::Null
+        };
+        let pointer = self.pointer.clone();
+        Ok(match self.kind {
+            FormKind::Replace => Edit::Replace { pointer, value },
+            FormKind::Rename => Edit::Rename {
+                pointer,
+                key: self.key.clone(),
+            },
+            FormKind::AddKey => Edit::Insert {
+                parent: pointer,
+                key: self.key.clone(),
+                value,
+            },
+            FormKind::Append => Edit::Insert {
+                parent: pointer,
+                key: "-".to_owned(),
+                value,
+            },
+            FormKind::InsertBefore => {
+                let (parent, index) = self.pointer.rsplit_once('/').unwrap_or_default();
+                Edit::Insert {
+                    parent: parent.to_owned(),
+                    key: index.to_owned(),
+                    value,
+                }
+            }
+        })
+    }
+}
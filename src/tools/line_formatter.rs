@@ -1,22 +1,34 @@
 use eframe::egui::{
-    Align, FontFamily, FontId, Frame, Layout, ScrollArea, TextEdit, TextFormat, TextStyle, Ui,
-    Vec2, text::LayoutJob, widgets::Label,
+    Align, Context, FontFamily, FontId, Frame, Layout, ScrollArea, TextEdit, TextFormat, TextStyle,
+    Ui, Vec2, text::LayoutJob, widgets::Label,
 };
-use once_cell::sync::Lazy;
-use regex::Regex;
+use serde::{Deserialize, Serialize};
 
-use crate::style;
-
-#[derive(PartialEq, Eq)]
-enum LineFormat {
-    LF,
-    CRLF,
-}
+use super::ToolAction;
+use crate::{
+    engine::stack::{self, LineFormat},
+    style,
+};
 
 pub struct LineFormatter {
     input: String,
     converted: LayoutJob,
     line_format: LineFormat,
+    /// 恢复状态后需要在下一帧重新格式化
+    pending_format: bool,
+}
+
+const ACTIONS: &[ToolAction] = &[
+    ToolAction::new("lf", "按 LF(\\n) 分行"),
+    ToolAction::new("crlf", "按 CRLF(\\r\\n) 分行"),
+    ToolAction::new("copy", "复制结果"),
+];
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    input: String,
+    line_format: LineFormat,
 }
 
 impl super::ToolItem for LineFormatter {
@@ -36,6 +48,10 @@ impl super::ToolItem for LineFormatter {
         let font_id = TextStyle::Monospace.resolve(ui.style());
         let line_height = ui.fonts(|fonts| fonts.row_height(&font_id));
         let input_rows = ((desired_height - label_height) / line_height).floor() as usize;
+        if self.pending_format {
+            self.format(ui.visuals().dark_mode);
+            self.pending_format = false;
+        }
 
         ui.horizontal(|ui| {
             ui.set_min_height(desired_height);
@@ -98,11 +114,11 @@ impl super::ToolItem for LineFormatter {
                     .inner_margin(4)
                     .corner_radius(6)
                     .show(ui, |ui| {
-                        let btns = vec![
-                            ui.selectable_value(&mut self.line_format, LineFormat::LF, "LF(\\n)"),
+                        let btns = [
+                            ui.selectable_value(&mut self.line_format, LineFormat::Lf, "LF(\\n)"),
                             ui.selectable_value(
                                 &mut self.line_format,
-                                LineFormat::CRLF,
+                                LineFormat::Crlf,
                                 "CRLF(\\r\\n)",
                             ),
                         ];
@@ -115,26 +131,61 @@ impl super::ToolItem for LineFormatter {
             },
         );
     }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            input: self.input.clone(),
+            line_format: self.line_format,
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.input = state.input;
+        self.line_format = state.line_format;
+        self.pending_format = true;
+    }
+
+    fn actions(&self) -> &[ToolAction] {
+        ACTIONS
+    }
+
+    fn run_action(&mut self, id: &str, ctx: &Context) {
+        match id {
+            "lf" => {
+                self.line_format = LineFormat::Lf;
+                self.pending_format = true;
+            }
+            "crlf" => {
+                self.line_format = LineFormat::Crlf;
+                self.pending_format = true;
+            }
+            "copy" => ctx.copy_text(self.converted.text.clone()),
+            _ => {}
+        }
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_owned();
+        self.pending_format = true;
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.input.clone())
+    }
 }
 
 impl LineFormatter {
     fn format(&mut self, dark_mode: bool) {
-        let target = match self.line_format {
-            LineFormat::LF => "\\n",
-            LineFormat::CRLF => "\\r\\n",
-        };
-        let mut result = self.input.replace(target, "\n");
-        result = result.replace("\\t", "\t");
-        let lines = result.lines().collect::<Vec<_>>();
         self.converted = LayoutJob::default();
-        for line in lines {
-            let tf = if is_code_line(line) {
+        for line in stack::split(&self.input, self.line_format) {
+            let tf = if line.is_code {
                 TextFormat {
                     color: style::highlight_color(dark_mode),
                     font_id: FontId {
                         family: FontFamily::Monospace,
                         size: 12.0,
-                        ..Default::default()
                     },
                     ..Default::default()
                 }
@@ -143,12 +194,11 @@ impl LineFormatter {
                     font_id: FontId {
                         family: FontFamily::Monospace,
                         size: 12.0,
-                        ..Default::default()
                     },
                     ..Default::default()
                 }
             };
-            self.converted.append(&format!("{}\n", line), 0.0, tf);
+            self.converted.append(&format!("{}\n", line.text), 0.0, tf);
         }
     }
 }
@@ -158,12 +208,8 @@ impl Default for LineFormatter {
         LineFormatter {
             input: String::new(),
             converted: LayoutJob::default(),
-            line_format: LineFormat::LF,
+            line_format: LineFormat::Lf,
+            pending_format: false,
         }
     }
 }
-
-fn is_code_line(line: &str) -> bool {
-    static LINE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\w+:\d+( \+\w+)?").unwrap());
-    LINE_PATTERN.is_match(line)
-}
@@ -22,15 +22,6 @@ impl ServiceType {
             Self::Other(s) => s.as_str(),
         }
     }
-
-    fn service_names() -> Vec<&'static str> {
-        vec![
-            ServiceType::Issue.to_str(),
-            ServiceType::SimApi.to_str(),
-            ServiceType::SimMQ.to_str(),
-            ServiceType::SimCron.to_str(),
-        ]
-    }
 }
 
 fn deserialize_service_type<'de, D>(deserializer: D) -> Result<ServiceType, D::Error>
@@ -74,7 +65,7 @@ impl LogLine {
             },
         );
         layout.append(
-            &self.service.to_str(),
+            self.service.to_str(),
             12.0,
             TextFormat {
                 color: style::log_source_color(dark_mode),
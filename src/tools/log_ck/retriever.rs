@@ -1,4 +1,4 @@
-use eframe::egui::{Align, Button, Label, Layout, ScrollArea, TextEdit, Ui, text::LayoutJob};
+use eframe::egui::{Align, Button, Label, Layout, ScrollArea, Ui, text::LayoutJob};
 
 use super::log_line::{ClickhouseResponse, LogLine, ServiceType};
 use crate::style;
@@ -27,15 +27,15 @@ impl crate::tools::ToolItem for LogRetriever {
     }
 
     fn update(&mut self, ui: &mut Ui) {
-        if self.loading {
-            if let Some(lines) = self.inbox.read(ui).last() {
-                let mut job = LayoutJob::default();
-                for line in &lines {
-                    line.append_to_layout(&mut job, ui.visuals().dark_mode);
-                }
-                self.rendered = job;
-                self.loading = false;
+        if self.loading
+            && let Some(lines) = self.inbox.read(ui).last()
+        {
+            let mut job = LayoutJob::default();
+            for line in &lines {
+                line.append_to_layout(&mut job, ui.visuals().dark_mode);
             }
+            self.rendered = job;
+            self.loading = false;
         }
 
         ui.allocate_ui_with_layout(
@@ -56,7 +56,7 @@ impl crate::tools::ToolItem for LogRetriever {
                 if btn_response.inner.clicked() {
                     self.loading = true;
                     let tx = self.inbox.sender();
-                    let url = make_ck_query(&vec![self.trace_id.clone()]);
+                    let url = make_ck_query(std::slice::from_ref(&self.trace_id));
                     let mut req = ehttp::Request::get(url);
                     req.headers.insert("X-ClickHouse-User", USER);
                     req.headers.insert("X-ClickHouse-Key", PASSWORD);
@@ -1,10 +1,12 @@
-use eframe::egui::Ui;
+use eframe::egui::{Context, Ui};
 
 mod issue_jump;
 mod json_util;
 mod line_formatter;
 mod log_ck;
+mod registry;
 mod taskgraph;
+mod time_calc;
 mod timestamp;
 mod url_util;
 
@@ -12,12 +14,51 @@ pub use issue_jump::IssueJump;
 pub use json_util::JsonConverter;
 pub use line_formatter::LineFormatter;
 pub use log_ck::LogRetriever;
+pub use registry::{Availability, Category, ToolInfo, ToolRegistry};
 pub use taskgraph::TaskGraphJump;
+pub use time_calc::TimeCalculator;
 pub use timestamp::TimestampConverter;
 pub use url_util::UrlConverter;
 
+/// 工具注册到命令面板中的操作
+#[derive(Clone, Copy, Debug)]
+pub struct ToolAction {
+    pub id: &'static str,
+    pub label: &'static str,
+}
+
+impl ToolAction {
+    pub const fn new(id: &'static str, label: &'static str) -> Self {
+        Self { id, label }
+    }
+}
+
 pub trait ToolItem {
     fn name(&self) -> &str;
     fn description(&self) -> &str;
     fn update(&mut self, ui: &mut Ui);
+
+    /// 需要在重启后恢复的状态
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// 恢复 [`ToolItem::save_state`] 保存的状态，无法识别的字段使用默认值
+    fn load_state(&mut self, _state: serde_json::Value) {}
+
+    /// 填入输入并立即转换，用于粘贴识别、链接跳转等场景
+    fn set_input(&mut self, _input: &str) {}
+
+    /// 当前输入，用于生成分享链接；返回 `None` 表示不支持分享
+    fn share_input(&self) -> Option<String> {
+        None
+    }
+
+    /// 可在命令面板中执行的操作
+    fn actions(&self) -> &[ToolAction] {
+        &[]
+    }
+
+    /// 执行 [`ToolItem::actions`] 中 id 对应的操作
+    fn run_action(&mut self, _id: &str, _ctx: &Context) {}
 }
@@ -0,0 +1,149 @@
+use super::*;
+
+/// 工具在边栏中的分组
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Convert,
+    Jump,
+    Debug,
+    Other(&'static str),
+}
+
+impl Category {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Category::Convert => "格式转换",
+            Category::Jump => "快速跳转",
+            Category::Debug => "排查",
+            Category::Other(label) => label,
+        }
+    }
+}
+
+/// 工具可以在哪些环境中使用
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Availability {
+    Always,
+    NativeOnly,
+    WebOnly,
+    /// 默认只在调试构建中显示，可在设置中打开
+    Experimental,
+}
+
+impl Availability {
+    /// 当前平台是否可以使用，实验性工具是否显示由设置决定
+    pub fn on_this_platform(&self) -> bool {
+        match self {
+            Availability::NativeOnly => !cfg!(target_arch = "wasm32"),
+            Availability::WebOnly => cfg!(target_arch = "wasm32"),
+            Availability::Always | Availability::Experimental => true,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Availability::Always => "",
+            Availability::NativeOnly => "仅桌面端",
+            Availability::WebOnly => "仅网页端",
+            Availability::Experimental => "实验性",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ToolInfo {
+    /// 唯一标识，用于保存状态
+    pub id: &'static str,
+    pub category: Category,
+    pub icon: &'static str,
+    pub availability: Availability,
+    pub create: fn() -> Box<dyn ToolItem>,
+}
+
+/// 所有可用工具的列表，边栏和设置页面都由它生成
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    tools: Vec<ToolInfo>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 内置工具
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(ToolInfo {
+                id: "timestamp",
+                category: Category::Convert,
+                icon: "⏰",
+                availability: Availability::Always,
+                create: || Box::new(TimestampConverter::default()),
+            })
+            .register(ToolInfo {
+                id: "timecalc",
+                category: Category::Convert,
+                icon: "⏱",
+                availability: Availability::Always,
+                create: || Box::new(TimeCalculator::default()),
+            })
+            .register(ToolInfo {
+                id: "json",
+                category: Category::Convert,
+                icon: "📋",
+                availability: Availability::Always,
+                create: || Box::new(JsonConverter::default()),
+            })
+            .register(ToolInfo {
+                id: "url",
+                category: Category::Convert,
+                icon: "🔗",
+                availability: Availability::Always,
+                create: || Box::new(UrlConverter::default()),
+            })
+            .register(ToolInfo {
+                id: "stack",
+                category: Category::Convert,
+                icon: "📚",
+                availability: Availability::Always,
+                create: || Box::new(LineFormatter::default()),
+            })
+            .register(ToolInfo {
+                id: "issue",
+                category: Category::Jump,
+                icon: "🐞",
+                availability: Availability::Always,
+                create: || Box::new(IssueJump::default()),
+            })
+            .register(ToolInfo {
+                id: "taskgraph",
+                category: Category::Jump,
+                icon: "🗺",
+                availability: Availability::Always,
+                create: || Box::new(TaskGraphJump::default()),
+            })
+            .register(ToolInfo {
+                id: "log",
+                category: Category::Debug,
+                icon: "📜",
+                availability: Availability::Experimental,
+                create: || Box::new(LogRetriever::default()),
+            });
+        registry
+    }
+
+    /// 注册工具，id 重复时替换已有的工具
+    pub fn register(&mut self, info: ToolInfo) -> &mut Self {
+        match self.tools.iter_mut().find(|tool| tool.id == info.id) {
+            Some(existing) => *existing = info,
+            None => self.tools.push(info),
+        }
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ToolInfo> {
+        self.tools.iter()
+    }
+}
@@ -1,9 +1,11 @@
 use eframe::egui::{Align, Button, Frame, Layout, Ui, output::OpenUrl};
+use serde::{Deserialize, Serialize};
 
 use crate::style;
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
 pub enum GraphType {
+    #[default]
     ReproduceTask,
     RetestTask,
     ReproduceResult,
@@ -28,6 +30,13 @@ pub struct TaskGraphJump {
     exec_id: String,
 }
 
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    exec_id: String,
+    graph_type: GraphType,
+}
+
 impl super::ToolItem for TaskGraphJump {
     fn name(&self) -> &str {
         "任务图快速跳转"
@@ -55,7 +64,7 @@ impl super::ToolItem for TaskGraphJump {
                         self.exec_id
                     );
                     let open_url = OpenUrl {
-                        url: url,
+                        url,
                         new_tab: true,
                     };
                     ui.ctx().open_url(open_url);
@@ -89,7 +98,7 @@ impl super::ToolItem for TaskGraphJump {
                         );
                         ui.selectable_value(
                             &mut self.graph_type,
-                            GraphType::RetestTask,
+                            GraphType::RetestResult,
                             "复测结果回流",
                         );
                         ui.selectable_value(
@@ -101,6 +110,28 @@ impl super::ToolItem for TaskGraphJump {
             },
         );
     }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            exec_id: self.exec_id.clone(),
+            graph_type: self.graph_type,
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.exec_id = state.exec_id;
+        self.graph_type = state.graph_type;
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.exec_id = input.trim().to_owned();
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.exec_id.clone())
+    }
 }
 
 impl Default for TaskGraphJump {
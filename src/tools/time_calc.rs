@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use eframe::egui::{Context, Grid, RichText, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    ToolAction,
+    timestamp::{copyable, zone_picker},
+};
+use crate::{
+    engine::timestamp::{self, Difference, TimeUnit, Zone},
+    style,
+};
+
+/// 计算两个时刻的差值，或给时刻加减一段时长
+#[derive(Default)]
+pub struct TimeCalculator {
+    start: String,
+    end: String,
+    base: String,
+    offset: String,
+    zone: Zone,
+    zone_input: String,
+    difference: Option<Result<Difference, String>>,
+    shifted: Option<Result<DateTime<Utc>, String>>,
+}
+
+const ACTIONS: &[ToolAction] = &[
+    ToolAction::new("end_now", "结束时间设为当前时间"),
+    ToolAction::new("swap", "交换开始与结束"),
+];
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    start: String,
+    end: String,
+    base: String,
+    offset: String,
+    zone: Zone,
+}
+
+impl super::ToolItem for TimeCalculator {
+    fn name(&self) -> &str {
+        "时间计算"
+    }
+
+    fn description(&self) -> &str {
+        "计算两个时间的差值，或对时间加减时长"
+    }
+
+    fn update(&mut self, ui: &mut Ui) {
+        let warn_color = style::warn_color(ui.visuals().dark_mode);
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("时区");
+            changed |= zone_picker(ui, "calc_zone", &mut self.zone, &mut self.zone_input);
+        });
+
+        ui.separator();
+        ui.heading("时间差");
+        Grid::new("difference_inputs")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for (label, input) in [("开始", &mut self.start), ("结束", &mut self.end)] {
+                    ui.label(label);
+                    ui.horizontal(|ui| {
+                        changed |= ui
+                            .add(
+                                TextEdit::singleline(input)
+                                    .hint_text("时间戳或时间字符串")
+                                    .desired_width(260.0),
+                            )
+                            .changed();
+                        if ui.small_button("现在").clicked() {
+                            *input = timestamp::now(TimeUnit::Milli, self.zone).0;
+                            changed = true;
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        match &self.difference {
+            Some(Ok(difference)) => {
+                Grid::new("difference").num_columns(2).show(ui, |ui| {
+                    ui.label("相差");
+                    copyable(ui, timestamp::humanize(difference.delta));
+                    ui.end_row();
+                    for (label, unit) in [
+                        ("秒", TimeUnit::Sec),
+                        ("毫秒", TimeUnit::Milli),
+                        ("微秒", TimeUnit::Micro),
+                        ("纳秒", TimeUnit::Nano),
+                    ] {
+                        ui.label(label);
+                        match difference.in_unit(unit) {
+                            Some(value) => copyable(ui, value.to_string()),
+                            None => {
+                                ui.weak("超出范围");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+            Some(Err(e)) => {
+                ui.label(RichText::new(e).color(warn_color));
+            }
+            None => {}
+        }
+
+        ui.separator();
+        ui.heading("时间加减");
+        Grid::new("shift_inputs").num_columns(2).show(ui, |ui| {
+            ui.label("时间");
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        TextEdit::singleline(&mut self.base)
+                            .hint_text("时间戳或时间字符串")
+                            .desired_width(260.0),
+                    )
+                    .changed();
+                if ui.small_button("现在").clicked() {
+                    self.base = timestamp::now(TimeUnit::Milli, self.zone).0;
+                    changed = true;
+                }
+            });
+            ui.end_row();
+            ui.label("时长");
+            changed |= ui
+                .add(
+                    TextEdit::singleline(&mut self.offset)
+                        .hint_text("如 +1h30m、-250ms、2d")
+                        .desired_width(260.0),
+                )
+                .changed();
+            ui.end_row();
+        });
+        match self.shifted {
+            Some(Ok(instant)) => {
+                Grid::new("shifted").num_columns(2).show(ui, |ui| {
+                    ui.label("结果");
+                    copyable(
+                        ui,
+                        timestamp::format_in(instant, self.zone, TimeUnit::Milli),
+                    );
+                    ui.end_row();
+                    ui.label("毫秒时间戳");
+                    copyable(ui, instant.timestamp_millis().to_string());
+                    ui.end_row();
+                });
+            }
+            Some(Err(ref e)) => {
+                ui.label(RichText::new(e).color(warn_color));
+            }
+            None => {}
+        }
+
+        if changed {
+            self.calculate();
+        }
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            start: self.start.clone(),
+            end: self.end.clone(),
+            base: self.base.clone(),
+            offset: self.offset.clone(),
+            zone: self.zone,
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.start = state.start;
+        self.end = state.end;
+        self.base = state.base;
+        self.offset = state.offset;
+        self.zone = state.zone;
+        self.calculate();
+    }
+
+    fn actions(&self) -> &[ToolAction] {
+        ACTIONS
+    }
+
+    fn run_action(&mut self, id: &str, _ctx: &Context) {
+        match id {
+            "end_now" => self.end = timestamp::now(TimeUnit::Milli, self.zone).0,
+            "swap" => std::mem::swap(&mut self.start, &mut self.end),
+            _ => return,
+        }
+        self.calculate();
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.start = input.trim().to_owned();
+        self.calculate();
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.start.clone())
+    }
+}
+
+impl TimeCalculator {
+    fn calculate(&mut self) {
+        self.difference =
+            (!self.start.trim().is_empty() && !self.end.trim().is_empty()).then(|| {
+                let start = timestamp::parse_instant(&self.start, self.zone)
+                    .map_err(|e| format!("开始时间：{e}"))?;
+                let end = timestamp::parse_instant(&self.end, self.zone)
+                    .map_err(|e| format!("结束时间：{e}"))?;
+                Ok(timestamp::difference(start, end))
+            });
+        self.shifted =
+            (!self.base.trim().is_empty() && !self.offset.trim().is_empty()).then(|| {
+                let base = timestamp::parse_instant(&self.base, self.zone)?;
+                let delta = timestamp::parse_duration(&self.offset)?;
+                timestamp::shift(base, delta).ok_or_else(|| "结果超出范围".to_owned())
+            });
+    }
+}
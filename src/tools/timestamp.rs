@@ -1,39 +1,72 @@
-#[cfg(not(target_arch = "wasm32"))]
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use chrono::{LocalResult, prelude::*};
-use eframe::egui::{Button, RichText, Ui, widgets::Label};
+use chrono::{DateTime, TimeDelta, Utc};
+use eframe::egui::{
+    Button, CollapsingHeader, ComboBox, Context, Grid, RichText, TextEdit, Ui, widgets::Label,
+};
+use serde::{Deserialize, Serialize};
 
-use crate::style;
+use super::ToolAction;
+use crate::{
+    engine::timestamp::{self, ALL_EPOCHS, COMMON_ZONES, Epoch, TimeUnit, Zone},
+    style,
+};
+use batch::{BatchView, SavedBatch};
 
-const DT_FORMAT_S: &str = "%F %T";
-const DT_FORMAT_MS: &str = "%F %T%.3f";
-const DT_FORMAT_US: &str = "%F %T%.6f";
-const DT_FORMAT_NS: &str = "%F %T%.9f";
-
-#[derive(PartialEq, Eq)]
-enum InputType {
-    Invalid,
-    Timestamp,
-    DateTimeStr,
-}
-
-#[derive(PartialEq, Eq)]
-enum TimeUnit {
-    Sec,
-    Milli,
-    Micro,
-    Nano,
-}
+mod batch;
 
 pub struct TimestampConverter {
     input: String,
     converted: String,
     format_warning: &'static str,
+    /// 输入为时间字符串时识别出的格式
+    matched_format: Option<&'static str>,
     copied_prompt: &'static str,
-    #[cfg(not(target_arch = "wasm32"))]
-    prompt_vanish_at: Instant,
+    /// 使用 chrono 的时钟，网页端没有 `Instant`
+    prompt_vanish_at: DateTime<Utc>,
+    unit: TimeUnit,
+    zone: Zone,
+    zone_input: String,
+    /// 多时区对照
+    show_zones: bool,
+    extra_zones: Vec<Zone>,
+    new_zone: Zone,
+    new_zone_input: String,
+    instant: Option<DateTime<Utc>>,
+    /// 自定义的 strftime 格式
+    custom_format: String,
+    batch: bool,
+    batch_view: BatchView,
+    /// 输入数值的时间基准
+    epoch: Epoch,
+    show_clock: bool,
+    /// 冻结时钟时的时刻
+    frozen_at: Option<DateTime<Utc>>,
+}
+
+const ACTIONS: &[ToolAction] = &[
+    ToolAction::new("now", "当前时间"),
+    ToolAction::new("copy", "复制结果"),
+    ToolAction::new("clear", "清空输入"),
+    ToolAction::new("unit_s", "以秒为单位"),
+    ToolAction::new("unit_ms", "以毫秒为单位"),
+    ToolAction::new("unit_us", "以微秒为单位"),
+    ToolAction::new("unit_ns", "以纳秒为单位"),
+];
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    input: String,
     unit: TimeUnit,
+    zone: Zone,
+    show_zones: bool,
+    extra_zones: Vec<Zone>,
+    custom_format: String,
+    batch: bool,
+    batch_state: SavedBatch,
+    epoch: Epoch,
+    show_clock: bool,
 }
 
 impl super::ToolItem for TimestampConverter {
@@ -46,11 +79,22 @@ impl super::ToolItem for TimestampConverter {
     }
 
     fn update(&mut self, ui: &mut Ui) {
-        #[cfg(not(target_arch = "wasm32"))]
-        if self.copied_prompt != "" && Instant::now() > self.prompt_vanish_at {
+        if !self.copied_prompt.is_empty() && Utc::now() > self.prompt_vanish_at {
             self.copied_prompt = "";
         }
 
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.batch, false, "单个");
+            ui.selectable_value(&mut self.batch, true, "批量");
+        });
+        if self.batch {
+            if self.options_ui(ui) {
+                self.batch_view.convert(self.unit, self.zone);
+            }
+            self.batch_view.show(ui, self.unit, self.zone);
+            return;
+        }
+
         let mut responses = vec![];
         ui.scope(|ui| {
             ui.spacing_mut().item_spacing = (4.0, 4.0).into();
@@ -73,26 +117,34 @@ impl super::ToolItem for TimestampConverter {
                 .on_hover_text("点击复制")
                 .clicked()
             {
-                ui.ctx().copy_text(self.converted.clone());
-                self.copied_prompt = "已复制";
-                #[cfg(not(target_arch = "wasm32"))]
-                {
-                    self.prompt_vanish_at = Instant::now() + Duration::from_secs(2);
-                }
+                self.copy_result(ui.ctx());
             }
             ui.add(Label::new(
                 RichText::new(self.copied_prompt)
                     .color(style::prompt_color(ui.visuals().dark_mode)),
             ));
         });
-        ui.horizontal(|ui| {
-            responses.extend([
-                ui.selectable_value(&mut self.unit, TimeUnit::Sec, "秒"),
-                ui.selectable_value(&mut self.unit, TimeUnit::Milli, "毫秒"),
-                ui.selectable_value(&mut self.unit, TimeUnit::Micro, "微秒"),
-                ui.selectable_value(&mut self.unit, TimeUnit::Nano, "纳秒"),
-            ]);
-        });
+        if let Some(format) = self.matched_format {
+            ui.weak(format!("识别为 {format}"));
+        }
+        if self.epoch == Epoch::Unix
+            && let Some(epoch) = Epoch::guess(&self.input)
+            && ui
+                .button(format!("看起来像 {}，按此解析", epoch.label()))
+                .clicked()
+        {
+            self.epoch = epoch;
+            self.convert();
+        }
+        let options_changed = self.options_ui(ui);
+        if self.show_zones {
+            self.zones_ui(ui);
+        }
+        if let Some(instant) = self.instant {
+            CollapsingHeader::new("更多格式")
+                .id_salt("renderings")
+                .show(ui, |ui| self.renderings_ui(ui, instant));
+        }
         ui.separator();
         ui.horizontal(|ui| {
             // 当前时间按钮
@@ -101,89 +153,326 @@ impl super::ToolItem for TimestampConverter {
                 ui.add(Button::new("当前时间").fill(style::primary_color(ui.visuals().dark_mode)))
             });
             if btn_response.inner.clicked() {
-                let now = Local::now().naive_local();
-                self.input = format!("{}", now.and_utc().timestamp_millis());
-                self.converted = now.format(DT_FORMAT_MS).to_string();
-                self.format_warning = "";
+                self.now();
             }
+            ui.checkbox(&mut self.show_clock, "实时时钟");
         });
+        if self.show_clock {
+            self.clock_ui(ui);
+        }
 
-        if responses.iter().any(|r| r.changed()) {
-            let input = self.input.trim();
+        if options_changed || responses.iter().any(|r| r.changed()) {
             self.copied_prompt = "";
-            let mut input_type = InputType::Invalid;
-            if input.is_empty() {
-                self.converted.clear();
-            } else if input.len() > 10 {
-                let (s, r) = input.split_at(10);
-                if let (Ok(secs), Ok(rr)) = (s.parse::<i64>(), r.parse::<u32>()) {
-                    input_type = InputType::Timestamp;
-                    let nsecs = rr * 10_u32.pow(9 - r.len() as u32);
-                    //let dt = NaiveDateTime::from_timestamp(secs, nsecs);
-                    match Local.timestamp_opt(secs, nsecs) {
-                        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => {
-                            self.converted = match self.unit {
-                                TimeUnit::Sec => dt.format(DT_FORMAT_S).to_string(),
-                                TimeUnit::Milli => dt.format(DT_FORMAT_MS).to_string(),
-                                TimeUnit::Micro => dt.format(DT_FORMAT_US).to_string(),
-                                TimeUnit::Nano => dt.format(DT_FORMAT_NS).to_string(),
-                            };
+            self.convert();
+        }
+    }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            input: self.input.clone(),
+            unit: self.unit,
+            zone: self.zone,
+            show_zones: self.show_zones,
+            extra_zones: self.extra_zones.clone(),
+            custom_format: self.custom_format.clone(),
+            batch: self.batch,
+            batch_state: self.batch_view.save(),
+            epoch: self.epoch,
+            show_clock: self.show_clock,
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.input = state.input;
+        self.unit = state.unit;
+        self.zone = state.zone;
+        self.show_zones = state.show_zones;
+        self.extra_zones = state.extra_zones;
+        self.custom_format = state.custom_format;
+        self.batch = state.batch;
+        self.epoch = state.epoch;
+        self.show_clock = state.show_clock;
+        self.batch_view
+            .load(state.batch_state, self.unit, self.zone);
+        self.convert();
+    }
+
+    fn actions(&self) -> &[ToolAction] {
+        ACTIONS
+    }
+
+    fn run_action(&mut self, id: &str, ctx: &Context) {
+        match id {
+            "now" => self.now(),
+            "copy" => self.copy_result(ctx),
+            "clear" => {
+                self.input.clear();
+                self.convert();
+            }
+            "unit_s" | "unit_ms" | "unit_us" | "unit_ns" => {
+                self.unit = match id {
+                    "unit_s" => TimeUnit::Sec,
+                    "unit_ms" => TimeUnit::Milli,
+                    "unit_us" => TimeUnit::Micro,
+                    _ => TimeUnit::Nano,
+                };
+                self.convert();
+            }
+            _ => {}
+        }
+    }
+
+    fn set_input(&mut self, input: &str) {
+        if input.trim().contains('\n') {
+            self.batch = true;
+            self.batch_view.input = input.to_owned();
+            self.batch_view.convert(self.unit, self.zone);
+            return;
+        }
+        self.batch = false;
+        self.input = input.trim().to_owned();
+        self.copied_prompt = "";
+        self.convert();
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.input.clone())
+    }
+}
+
+impl TimestampConverter {
+    fn now(&mut self) {
+        self.input = match self.epoch {
+            Epoch::Unix => timestamp::now(self.unit, self.zone).0,
+            epoch => epoch.from_instant(Utc::now(), self.zone),
+        };
+        self.convert();
+    }
+
+    fn copy_result(&mut self, ctx: &Context) {
+        ctx.copy_text(self.converted.clone());
+        self.copied_prompt = "已复制";
+        self.prompt_vanish_at = Utc::now() + TimeDelta::seconds(2);
+    }
+
+    fn convert(&mut self) {
+        let converted = timestamp::convert_epoch(&self.input, self.epoch, self.unit, self.zone);
+        self.converted = converted.output;
+        self.format_warning = converted.warning.map_or("", |w| w.message());
+        self.instant = converted.instant;
+        self.matched_format = converted.format;
+    }
+
+    /// 实时刷新的当前时间，可以冻结后逐项复制
+    fn clock_ui(&mut self, ui: &mut Ui) {
+        let now = self.frozen_at.unwrap_or_else(Utc::now);
+        Grid::new("clock")
+            .num_columns(2)
+            .spacing((16.0, 4.0))
+            .show(ui, |ui| {
+                for row in timestamp::clock(now, self.zone) {
+                    ui.label(row.label);
+                    copyable(ui, row.value);
+                    ui.end_row();
+                }
+            });
+        let label = if self.frozen_at.is_some() {
+            "▶ 继续"
+        } else {
+            "⏸ 冻结"
+        };
+        if ui.button(label).clicked() {
+            self.frozen_at = match self.frozen_at {
+                Some(_) => None,
+                None => Some(now),
+            };
+        }
+        if self.frozen_at.is_none() {
+            ui.ctx().request_repaint_after(Duration::from_millis(50));
+        }
+    }
+
+    /// 单位与时区选项，返回是否有改动
+    fn options_ui(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            for (unit, label) in [
+                (TimeUnit::Sec, "秒"),
+                (TimeUnit::Milli, "毫秒"),
+                (TimeUnit::Micro, "微秒"),
+                (TimeUnit::Nano, "纳秒"),
+            ] {
+                changed |= ui.selectable_value(&mut self.unit, unit, label).changed();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("时区");
+            changed |= zone_picker(ui, "zone", &mut self.zone, &mut self.zone_input);
+            if !self.batch {
+                ui.checkbox(&mut self.show_zones, "多时区对照");
+            }
+        });
+        if !self.batch {
+            ui.horizontal(|ui| {
+                ui.label("时间基准");
+                ComboBox::from_id_salt("epoch")
+                    .selected_text(self.epoch.label())
+                    .show_ui(ui, |ui| {
+                        for epoch in ALL_EPOCHS {
+                            changed |= ui
+                                .selectable_value(&mut self.epoch, *epoch, epoch.label())
+                                .changed();
                         }
-                        _ => self.format_warning = "时间解析失败",
-                    }
-                } else if let Ok(dt) = NaiveDateTime::parse_from_str(input, DT_FORMAT_MS) {
-                    input_type = InputType::DateTimeStr;
-                    match Local.from_local_datetime(&dt) {
-                        LocalResult::Single(dt) => {
-                            if let Some(ns) = dt.timestamp_nanos_opt() {
-                                let scale = match self.unit {
-                                    TimeUnit::Sec => 1e9 as i64,
-                                    TimeUnit::Milli => 1e6 as i64,
-                                    TimeUnit::Micro => 1e3 as i64,
-                                    TimeUnit::Nano => 1,
-                                };
-                                self.converted = format!("{}", ns / scale);
-                            } else {
-                                self.format_warning = "时间解析失败";
-                            }
+                    });
+            });
+        }
+        changed
+    }
+
+    /// 同一时刻的多种表示形式，以及自定义格式
+    fn renderings_ui(&mut self, ui: &mut Ui, instant: DateTime<Utc>) {
+        Grid::new("renderings")
+            .num_columns(2)
+            .spacing((16.0, 4.0))
+            .show(ui, |ui| {
+                for rendering in timestamp::renderings(instant, self.zone, Utc::now()) {
+                    ui.label(rendering.label);
+                    copyable(ui, rendering.value);
+                    ui.end_row();
+                }
+                ui.label("自定义格式");
+                ui.add(
+                    TextEdit::singleline(&mut self.custom_format)
+                        .hint_text("strftime 格式，如 %Y年%m月%d日 %H:%M")
+                        .desired_width(260.0),
+                );
+                ui.end_row();
+                if !self.custom_format.is_empty() {
+                    ui.label("");
+                    match timestamp::format_custom(instant, self.zone, &self.custom_format) {
+                        Ok(text) => copyable(ui, text),
+                        Err(e) => {
+                            ui.label(
+                                RichText::new(e).color(style::warn_color(ui.visuals().dark_mode)),
+                            );
                         }
-                        _ => self.format_warning = "无法转换时间戳到本地时区",
                     }
+                    ui.end_row();
                 }
-            } else if let Ok(secs) = input.parse::<i64>() {
-                // len <= 10
-                input_type = InputType::Timestamp;
-                //let dt = NaiveDateTime::from_timestamp(secs, 0);
-                match Local.timestamp_opt(secs, 0) {
-                    LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => {
-                        self.converted = dt.format(DT_FORMAT_S).to_string();
+            });
+    }
+
+    /// 同一时刻在其他时区的时间
+    fn zones_ui(&mut self, ui: &mut Ui) {
+        let mut removed = None;
+        Grid::new("extra_zones")
+            .num_columns(3)
+            .spacing((16.0, 4.0))
+            .show(ui, |ui| {
+                for (i, zone) in self.extra_zones.iter().enumerate() {
+                    ui.label(zone.label());
+                    match self.instant {
+                        Some(instant) => {
+                            copyable(ui, timestamp::format_in(instant, *zone, self.unit));
+                        }
+                        None => {
+                            ui.label("-");
+                        }
+                    }
+                    if ui.add(Button::new("❌").frame(false)).clicked() {
+                        removed = Some(i);
                     }
-                    LocalResult::None => self.format_warning = "时间解析失败",
+                    ui.end_row();
+                }
+            });
+        if let Some(i) = removed {
+            self.extra_zones.remove(i);
+        }
+        ui.horizontal(|ui| {
+            zone_picker(ui, "new_zone", &mut self.new_zone, &mut self.new_zone_input);
+            if ui.button("➕ 添加").clicked() && !self.extra_zones.contains(&self.new_zone) {
+                self.extra_zones.push(self.new_zone);
+            }
+        });
+    }
+}
+
+/// 点击即可复制的文本
+pub(super) fn copyable(ui: &mut Ui, text: String) {
+    if ui
+        .add(Button::new(&text).frame(false))
+        .on_hover_text("点击复制")
+        .clicked()
+    {
+        ui.ctx().copy_text(text);
+    }
+}
+
+/// 常用时区的下拉框，以及输入其他时区的文本框。返回时区是否改变。
+pub(super) fn zone_picker(
+    ui: &mut Ui,
+    id_salt: &str,
+    zone: &mut Zone,
+    custom: &mut String,
+) -> bool {
+    let mut changed = false;
+    ComboBox::from_id_salt(id_salt)
+        .selected_text(zone.label())
+        .show_ui(ui, |ui| {
+            for option in COMMON_ZONES {
+                if ui.selectable_value(zone, *option, option.label()).changed() {
+                    custom.clear();
+                    changed = true;
                 }
             }
-            let len = input.len();
-            self.format_warning = match input_type {
-                InputType::Timestamp if len != 10 && len != 13 && len != 16 && len != 19 => {
-                    "⚠ 非标准时间戳格式（秒/毫秒/微秒/纳秒）"
+        });
+    let response = ui.add(
+        TextEdit::singleline(custom)
+            .hint_text("其他时区，如 +05:30、Europe/Paris")
+            .desired_width(220.0),
+    );
+    if !custom.trim().is_empty() {
+        match custom.parse::<Zone>() {
+            Ok(parsed) => {
+                if response.changed() && parsed != *zone {
+                    *zone = parsed;
+                    changed = true;
                 }
-                InputType::Invalid => "⚠ 无效输入",
-                _ => "",
-            };
+            }
+            Err(e) => {
+                ui.label(RichText::new(e).color(style::warn_color(ui.visuals().dark_mode)));
+            }
         }
     }
+    changed
 }
 
 impl Default for TimestampConverter {
     fn default() -> Self {
-        let now = Local::now().naive_local();
+        let (input, converted) = timestamp::now(TimeUnit::Milli, Zone::Local);
         TimestampConverter {
-            input: format!("{}", now.and_utc().timestamp_millis()),
-            converted: now.format(DT_FORMAT_MS).to_string(),
+            input,
+            converted,
             format_warning: "",
+            matched_format: None,
             copied_prompt: "",
             unit: TimeUnit::Milli,
-            #[cfg(not(target_arch = "wasm32"))]
-            prompt_vanish_at: Instant::now(),
+            zone: Zone::Local,
+            zone_input: String::new(),
+            show_zones: false,
+            extra_zones: vec![Zone::Utc],
+            new_zone: Zone::Utc,
+            new_zone_input: String::new(),
+            instant: Some(Utc::now()),
+            custom_format: String::new(),
+            batch: false,
+            batch_view: BatchView::default(),
+            epoch: Epoch::Unix,
+            show_clock: false,
+            frozen_at: None,
+            prompt_vanish_at: Utc::now(),
         }
     }
 }
@@ -0,0 +1,114 @@
+use eframe::egui::{Button, Grid, RichText, ScrollArea, TextEdit, TextWrapMode, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    engine::timestamp::{self, BatchMode, BatchRow, TimeUnit, Zone},
+    style,
+};
+
+/// 批量模式：左侧输入多行文本，右侧逐个显示转换结果
+#[derive(Default)]
+pub(super) struct BatchView {
+    pub(super) input: String,
+    mode: BatchMode,
+    rows: Vec<BatchRow>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct SavedBatch {
+    input: String,
+    mode: BatchMode,
+}
+
+impl BatchView {
+    pub(super) fn show(&mut self, ui: &mut Ui, unit: TimeUnit, zone: Zone) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            changed |= ui
+                .selectable_value(&mut self.mode, BatchMode::Lines, "逐行转换")
+                .changed();
+            changed |= ui
+                .selectable_value(&mut self.mode, BatchMode::Tokens, "提取文本中的时间")
+                .changed();
+            if ui
+                .add_enabled(!self.rows.is_empty(), Button::new("复制为 TSV"))
+                .clicked()
+            {
+                ui.ctx().copy_text(timestamp::to_tsv(&self.rows));
+            }
+        });
+        ui.columns(2, |columns| {
+            ScrollArea::vertical()
+                .id_salt("batch_input")
+                .max_height(400.0)
+                .show(&mut columns[0], |ui| {
+                    changed |= ui
+                        .add(
+                            TextEdit::multiline(&mut self.input)
+                                .hint_text("每行一个时间戳或时间字符串")
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(12),
+                        )
+                        .changed();
+                });
+            ScrollArea::both()
+                .id_salt("batch_output")
+                .max_height(400.0)
+                .show(&mut columns[1], |ui| self.rows_ui(ui));
+        });
+        if changed {
+            self.convert(unit, zone);
+        }
+    }
+
+    pub(super) fn convert(&mut self, unit: TimeUnit, zone: Zone) {
+        self.rows = timestamp::convert_batch(&self.input, self.mode, unit, zone);
+    }
+
+    pub(super) fn save(&self) -> SavedBatch {
+        SavedBatch {
+            input: self.input.clone(),
+            mode: self.mode,
+        }
+    }
+
+    pub(super) fn load(&mut self, saved: SavedBatch, unit: TimeUnit, zone: Zone) {
+        self.input = saved.input;
+        self.mode = saved.mode;
+        self.convert(unit, zone);
+    }
+
+    fn rows_ui(&self, ui: &mut Ui) {
+        let warn_color = style::warn_color(ui.visuals().dark_mode);
+        Grid::new("batch_rows")
+            .num_columns(4)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("行");
+                ui.strong("输入");
+                ui.strong("输出");
+                ui.strong("警告");
+                ui.end_row();
+                for row in &self.rows {
+                    ui.weak(row.line.to_string());
+                    ui.label(&row.input);
+                    if ui
+                        .add(
+                            Button::new(&row.output)
+                                .frame(false)
+                                .wrap_mode(TextWrapMode::Extend),
+                        )
+                        .on_hover_text("点击复制")
+                        .clicked()
+                    {
+                        ui.ctx().copy_text(row.output.clone());
+                    }
+                    if let Some(warning) = row.warning {
+                        ui.label(RichText::new(warning.message()).color(warn_color));
+                    }
+                    ui.end_row();
+                }
+            });
+    }
+}
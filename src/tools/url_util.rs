@@ -1,14 +1,14 @@
 use eframe::egui::{
-    Align, Frame, Layout, RichText, ScrollArea, TextEdit, TextStyle, Ui, Vec2, widgets::Label,
+    Align, Context, Frame, Layout, RichText, ScrollArea, TextEdit, TextStyle, Ui, Vec2,
+    widgets::Label,
 };
+use serde::{Deserialize, Serialize};
 
-use crate::style;
-
-#[derive(PartialEq, Eq)]
-enum Conversion {
-    Encode,
-    Decode,
-}
+use super::ToolAction;
+use crate::{
+    engine::url::{self, Conversion},
+    style,
+};
 
 pub struct UrlConverter {
     input: String,
@@ -17,6 +17,19 @@ pub struct UrlConverter {
     warning: String,
 }
 
+const ACTIONS: &[ToolAction] = &[
+    ToolAction::new("encode", "编码"),
+    ToolAction::new("decode", "解码"),
+    ToolAction::new("copy", "复制结果"),
+];
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SavedState {
+    input: String,
+    conversion: Conversion,
+}
+
 impl super::ToolItem for UrlConverter {
     fn name(&self) -> &str {
         "URL 转换"
@@ -98,7 +111,7 @@ impl super::ToolItem for UrlConverter {
                     .inner_margin(4)
                     .corner_radius(6)
                     .show(ui, |ui| {
-                        let btns = vec![
+                        let btns = [
                             ui.selectable_value(&mut self.conversion, Conversion::Decode, "解码"),
                             ui.selectable_value(&mut self.conversion, Conversion::Encode, "编码"),
                         ];
@@ -117,16 +130,59 @@ impl super::ToolItem for UrlConverter {
             RichText::new(&self.warning).color(style::warn_color(ui.visuals().dark_mode)),
         ));
     }
+
+    fn save_state(&self) -> serde_json::Value {
+        serde_json::to_value(SavedState {
+            input: self.input.clone(),
+            conversion: self.conversion,
+        })
+        .unwrap_or_default()
+    }
+
+    fn load_state(&mut self, state: serde_json::Value) {
+        let state = serde_json::from_value::<SavedState>(state).unwrap_or_default();
+        self.input = state.input;
+        self.conversion = state.conversion;
+        self.convert();
+    }
+
+    fn actions(&self) -> &[ToolAction] {
+        ACTIONS
+    }
+
+    fn run_action(&mut self, id: &str, ctx: &Context) {
+        match id {
+            "encode" => {
+                self.conversion = Conversion::Encode;
+                self.convert();
+            }
+            "decode" => {
+                self.conversion = Conversion::Decode;
+                self.convert();
+            }
+            "copy" => ctx.copy_text(self.converted.clone()),
+            _ => {}
+        }
+    }
+
+    fn set_input(&mut self, input: &str) {
+        self.input = input.to_owned();
+        self.convert();
+    }
+
+    fn share_input(&self) -> Option<String> {
+        Some(self.input.clone())
+    }
 }
 
 impl UrlConverter {
     fn convert(&mut self) {
-        match self.conversion {
-            Conversion::Encode => self.converted = urlencoding::encode(&self.input).into_owned(),
-            Conversion::Decode => match urlencoding::decode(&self.input) {
-                Ok(decoded) => self.converted = decoded.into(),
-                Err(e) => self.warning = e.to_string(),
-            },
+        match url::convert(&self.input, self.conversion) {
+            Ok(converted) => {
+                self.converted = converted;
+                self.warning.clear();
+            }
+            Err(e) => self.warning = e,
         }
     }
 }